@@ -0,0 +1,113 @@
+use super::MiniBox;
+
+use core::ops::{Deref, DerefMut};
+
+/// A value with its alignment raised to at least `ALIGN`
+///
+/// `ALIGN` must be a power of two no larger than 4096. Because `MiniBox` picks its storage
+/// from the layout of the stored type, `MiniBox<Aligned<T, ALIGN>>` is heap allocated with
+/// the requested alignment whenever `ALIGN` is larger than the alignment of a pointer
+/// (smaller alignments are already satisfied by the inline storage), and it is deallocated
+/// with the same layout.
+///
+/// ```rust
+/// # use minibox::{Aligned, MiniBox};
+/// let bx = MiniBox::new_overaligned::<64>([1.0_f32; 4]);
+/// assert_eq!(&**bx as *const [f32; 4] as usize % 64, 0);
+/// assert_eq!(bx[3], 1.0);
+/// ```
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aligned<T, const ALIGN: usize>
+where
+    AlignTo<ALIGN>: ValidAlign,
+{
+    _align: [<AlignTo<ALIGN> as ValidAlign>::Marker; 0],
+    value: T,
+}
+
+/// A type-level alignment, used to bound `Aligned`
+pub struct AlignTo<const ALIGN: usize>;
+
+mod seal {
+    pub trait Sealed {}
+}
+
+/// Implemented for every `AlignTo<ALIGN>` where `ALIGN` is supported by `Aligned`
+pub trait ValidAlign: seal::Sealed {
+    #[doc(hidden)]
+    type Marker: Copy + Default + core::fmt::Debug + Eq + Ord + core::hash::Hash;
+}
+
+macro_rules! align {
+    ($($align:literal => $name:ident),* $(,)?) => {$(
+        #[doc(hidden)]
+        #[repr(align($align))]
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name;
+
+        impl seal::Sealed for AlignTo<$align> {}
+        impl ValidAlign for AlignTo<$align> {
+            type Marker = $name;
+        }
+    )*};
+}
+
+align! {
+    1 => Align1, 2 => Align2, 4 => Align4, 8 => Align8,
+    16 => Align16, 32 => Align32, 64 => Align64, 128 => Align128,
+    256 => Align256, 512 => Align512, 1024 => Align1024, 2048 => Align2048,
+    4096 => Align4096,
+}
+
+impl<T, const ALIGN: usize> Aligned<T, ALIGN>
+where
+    AlignTo<ALIGN>: ValidAlign,
+{
+    /// Wrap `value`, raising its alignment to at least `ALIGN`
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { _align: [], value }
+    }
+
+    /// Unwrap the value
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, const ALIGN: usize> Deref for Aligned<T, ALIGN>
+where
+    AlignTo<ALIGN>: ValidAlign,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, const ALIGN: usize> DerefMut for Aligned<T, ALIGN>
+where
+    AlignTo<ALIGN>: ValidAlign,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> MiniBox<T> {
+    /// Create a new `MiniBox` whose value is aligned to at least `ALIGN`
+    ///
+    /// see `Aligned` for details
+    #[inline]
+    pub fn new_overaligned<const ALIGN: usize>(value: T) -> MiniBox<Aligned<T, ALIGN>>
+    where
+        AlignTo<ALIGN>: ValidAlign,
+    {
+        MiniBox::new(Aligned::new(value))
+    }
+}
@@ -0,0 +1,105 @@
+use super::MiniBox;
+
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use std::vec::Vec;
+
+/// A guard for initializing a `MiniBox<T>` one field at a time
+///
+/// The builder keeps track of which fields have been initialized, so if it is dropped before
+/// `build` is called (i.e. because initializing one of the fields panicked), only the initialized
+/// fields are dropped, and the storage is freed.
+///
+/// ```rust
+/// # use minibox::{MiniBox, MiniBoxBuilder};
+/// # use core::ptr::addr_of_mut;
+/// struct Large {
+///     name: String,
+///     data: [u64; 64],
+/// }
+///
+/// let mut builder = MiniBoxBuilder::<Large>::new();
+///
+/// unsafe {
+///     builder.init_field(|ptr| addr_of_mut!((*ptr).name), String::from("large"));
+///     builder.init_field(|ptr| addr_of_mut!((*ptr).data), [1; 64]);
+/// }
+///
+/// let bx: MiniBox<Large> = unsafe { builder.build() };
+/// assert_eq!(bx.name, "large");
+/// ```
+pub struct MiniBoxBuilder<T> {
+    storage: MiniBox<MaybeUninit<T>>,
+    // every initialized field that needs to be dropped, in initialization order
+    initialized: Vec<InitializedField>,
+}
+
+struct InitializedField {
+    // an offset instead of a pointer, because inline storage moves along with the builder
+    offset: usize,
+    drop: unsafe fn(*mut u8),
+}
+
+unsafe fn drop_field<F>(ptr: *mut u8) {
+    ptr.cast::<F>().drop_in_place()
+}
+
+impl<T> Default for MiniBoxBuilder<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MiniBoxBuilder<T> {
+    /// Create a new builder with uninitialized storage for a `T`
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            storage: MiniBox::new_uninit(),
+            initialized: Vec::new(),
+        }
+    }
+
+    /// Initialize the field that `project` points to with `value`
+    ///
+    /// # Safety
+    ///
+    /// * `project` must return a pointer to a field of the `T` that it is given, without
+    ///   reading from it or creating a reference to it (i.e. by using `core::ptr::addr_of_mut!`)
+    /// * each field must be initialized at most once
+    pub unsafe fn init_field<F, P: FnOnce(*mut T) -> *mut F>(&mut self, project: P, value: F) {
+        let base = self.storage.as_mut_ptr();
+        let field = project(base);
+        field.write(value);
+
+        if mem::needs_drop::<F>() {
+            let offset = field as usize - base as usize;
+            self.initialized.push(InitializedField {
+                offset,
+                drop: drop_field::<F>,
+            });
+        }
+    }
+
+    /// Get the initialized `MiniBox<T>`
+    ///
+    /// # Safety
+    ///
+    /// every field of `T` must be initialized
+    pub unsafe fn build(self) -> MiniBox<T> {
+        let this = ManuallyDrop::new(self);
+        drop(core::ptr::read(&this.initialized));
+        core::ptr::read(&this.storage).assume_init()
+    }
+}
+
+impl<T> Drop for MiniBoxBuilder<T> {
+    fn drop(&mut self) {
+        let base = self.storage.as_mut_ptr().cast::<u8>();
+
+        // drop in reverse initialization order, like a struct that failed to construct
+        for field in self.initialized.iter().rev() {
+            unsafe { (field.drop)(base.add(field.offset)) }
+        }
+    }
+}
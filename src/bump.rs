@@ -0,0 +1,162 @@
+use super::{MiniBox, MiniPtr, SizeClass};
+
+use bumpalo::Bump;
+use core::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+
+/// A `MiniBox` equivalent that allocates from a `bumpalo::Bump` arena instead of the global allocator
+///
+/// If `T` would be stored inline (or is zero-sized), this behaves exactly like a `MiniBox<T>`.
+/// Otherwise the value is placed in the arena, and dropping the `BumpMiniBox` only runs `T`'s
+/// destructor, the memory is reclaimed when the arena is reset or dropped.
+pub struct BumpMiniBox<'bump, T> {
+    ptr: MiniPtr<T>,
+    bump: PhantomData<&'bump Bump>,
+    drop: PhantomData<T>,
+}
+
+impl<T> MiniBox<T> {
+    /// Create a new `BumpMiniBox<T>`, allocating from `bump` if `T` can't be stored inline
+    #[inline]
+    pub fn new_in_bump(value: T, bump: &Bump) -> BumpMiniBox<'_, T> {
+        BumpMiniBox::new(value, bump)
+    }
+}
+
+impl<'bump, T> BumpMiniBox<'bump, T> {
+    /// The size class for `T`
+    pub const SIZE_CLASS: SizeClass = SizeClass::new::<T>();
+
+    /// Create a new `BumpMiniBox<T>`, allocating from `bump` if `T` can't be stored inline
+    #[inline]
+    pub fn new(value: T, bump: &'bump Bump) -> Self {
+        let ptr = match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => MiniBox::into_ptr(MiniBox::new(value)),
+            SizeClass::Boxed => MiniPtr::from_raw(bump.alloc(value)),
+        };
+
+        Self {
+            ptr,
+            bump: PhantomData,
+            drop: PhantomData,
+        }
+    }
+
+    /// Create a new `BumpMiniBox<T>` by initializing the value in place with `f`, allocating
+    /// from `bump` if `T` can't be stored inline
+    ///
+    /// see `MiniBox::emplace` for details
+    ///
+    /// # Safety
+    ///
+    /// `f` must fully initialize the value it is given (if it returns)
+    #[inline]
+    pub unsafe fn emplace_in<F: FnOnce(&mut MaybeUninit<T>)>(bump: &'bump Bump, f: F) -> Self {
+        let ptr = match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => MiniBox::into_ptr(MiniBox::emplace(f)),
+            SizeClass::Boxed => {
+                let slot = bump
+                    .alloc_layout(Layout::new::<T>())
+                    .cast::<MaybeUninit<T>>();
+                f(&mut *slot.as_ptr());
+                MiniPtr::from_raw(slot.as_ptr().cast())
+            }
+        };
+
+        Self {
+            ptr,
+            bump: PhantomData,
+            drop: PhantomData,
+        }
+    }
+
+    /// Convert the `BumpMiniBox` into a `MiniPtr` without dropping the underlying value
+    ///
+    /// `BumpMiniBox` doesn't store a reference to its arena (only its lifetime), so unlike
+    /// `Box::into_raw_with_allocator` the arena isn't returned. Use `BumpMiniBox::from_raw_in`
+    /// with the same arena to reconstruct the `BumpMiniBox`
+    #[inline]
+    pub fn into_raw(bx: Self) -> MiniPtr<T> {
+        let ptr = bx.ptr;
+        core::mem::forget(bx);
+        ptr
+    }
+
+    /// Create a `BumpMiniBox` from a `MiniPtr` returned by `BumpMiniBox::into_raw`
+    ///
+    /// ```rust
+    /// # use minibox::BumpMiniBox;
+    /// # use bumpalo::Bump;
+    /// let bump = Bump::new();
+    /// let ptr = BumpMiniBox::into_raw(BumpMiniBox::new([1_u64; 4], &bump));
+    /// let bx = unsafe { BumpMiniBox::from_raw_in(ptr, &bump) };
+    /// assert_eq!(*bx, [1; 4]);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have come from `BumpMiniBox::into_raw` on a `BumpMiniBox` allocated in `bump`,
+    /// and must not be used after this function
+    #[inline]
+    pub unsafe fn from_raw_in(ptr: MiniPtr<T>, bump: &'bump Bump) -> Self {
+        let _ = bump;
+
+        Self {
+            ptr,
+            bump: PhantomData,
+            drop: PhantomData,
+        }
+    }
+
+    /// Consume the `BumpMiniBox` returning the underlying data.
+    ///
+    /// The arena memory used by the value (if any) is not reclaimed until the arena is reset
+    #[inline]
+    pub fn into_inner(bx: Self) -> T {
+        let ptr = bx.ptr;
+        core::mem::forget(bx);
+
+        unsafe {
+            match Self::SIZE_CLASS {
+                SizeClass::Zero | SizeClass::Inline => MiniBox::into_inner(MiniBox::from_ptr(ptr)),
+                SizeClass::Boxed => ptr.to_raw().read(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for BumpMiniBox<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            match Self::SIZE_CLASS {
+                SizeClass::Zero | SizeClass::Inline => drop(MiniBox::from_ptr(self.ptr)),
+                // the arena owns the allocation, so only drop the value
+                SizeClass::Boxed => self.ptr.to_raw().drop_in_place(),
+            }
+        }
+    }
+}
+
+impl<T> Deref for BumpMiniBox<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // `MiniPtr::as_ref` only requires the pointer to be valid, not that
+        // it was allocated with the global allocator
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for BumpMiniBox<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+unsafe impl<T: Send> Send for BumpMiniBox<'_, T> {}
+unsafe impl<T: Sync> Sync for BumpMiniBox<'_, T> {}
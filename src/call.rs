@@ -0,0 +1,80 @@
+use super::MiniBox;
+
+/// A stable equivalent of `FnOnce` for `MiniBox`, see `MiniFn` for details
+pub trait MiniFnOnce<Args> {
+    /// The return type of the function
+    type Output;
+
+    /// Call the function, consuming it
+    fn call_once(self, args: Args) -> Self::Output;
+}
+
+/// A stable equivalent of `FnMut` for `MiniBox`, see `MiniFn` for details
+pub trait MiniFnMut<Args>: MiniFnOnce<Args> {
+    /// Call the function by mutable reference
+    fn call_mut(&mut self, args: Args) -> Self::Output;
+}
+
+/// A stable equivalent of `Fn` for `MiniBox`
+///
+/// With the `nightly` feature `MiniBox<F>` implements `FnOnce`, `FnMut`, and `Fn`, so it can be called
+/// directly. On stable, implementing those traits isn't possible, so these traits provide `call_once`,
+/// `call_mut`, and `call` methods instead, which take the arguments as a tuple (for up to 6 arguments)
+///
+/// ```rust
+/// use minibox::{MiniBox, MiniFn, MiniFnMut};
+///
+/// let offset = 10;
+/// let add = MiniBox::new(move |a: u32, b: u32| a + b + offset);
+/// assert_eq!(add.call((1, 2)), 13);
+///
+/// let mut count = 0;
+/// let mut increment = MiniBox::new(|| count += 1);
+/// increment.call_mut(());
+/// increment.call_mut(());
+/// drop(increment);
+/// assert_eq!(count, 2);
+/// ```
+pub trait MiniFn<Args>: MiniFnMut<Args> {
+    /// Call the function by shared reference
+    fn call(&self, args: Args) -> Self::Output;
+}
+
+macro_rules! call {
+    ($($arg:ident)*) => {
+        call!{@next $($arg)*}
+
+        impl<Func: FnOnce($($arg),*) -> R, R, $($arg),*> MiniFnOnce<($($arg,)*)> for MiniBox<Func> {
+            type Output = R;
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn call_once(self, ($($arg,)*): ($($arg,)*)) -> R {
+                MiniBox::into_inner(self)($($arg),*)
+            }
+        }
+
+        impl<Func: FnMut($($arg),*) -> R, R, $($arg),*> MiniFnMut<($($arg,)*)> for MiniBox<Func> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn call_mut(&mut self, ($($arg,)*): ($($arg,)*)) -> R {
+                (**self)($($arg),*)
+            }
+        }
+
+        impl<Func: Fn($($arg),*) -> R, R, $($arg),*> MiniFn<($($arg,)*)> for MiniBox<Func> {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn call(&self, ($($arg,)*): ($($arg,)*)) -> R {
+                (**self)($($arg),*)
+            }
+        }
+    };
+
+    (@next ) => {};
+    (@next $first:ident $($arg:ident)*) => {
+        call!{$($arg)*}
+    };
+}
+
+call! { A B C D E F }
@@ -0,0 +1,145 @@
+use super::MiniBox;
+
+use core::fmt;
+use core::ops::Deref;
+
+/// A clone-on-write smart pointer, like `std::borrow::Cow`, that stores owned values in a `MiniBox`
+///
+/// The borrowed variant is just a reference, and the owned variant only allocates if the value is too
+/// big to store inline, so promoting a small value with `MiniCow::to_mut` never allocates
+///
+/// ```rust
+/// # use minibox::MiniCow;
+/// fn clamp(values: &[i32; 4]) -> MiniCow<'_, [i32; 4]> {
+///     let mut values = MiniCow::from_borrowed(values);
+///     for i in 0..4 {
+///         if values[i] < 0 {
+///             values.to_mut()[i] = 0;
+///         }
+///     }
+///     values
+/// }
+///
+/// assert!(clamp(&[1, 2, 3, 4]).is_borrowed());
+///
+/// let clamped = clamp(&[1, -2, 3, -4]);
+/// assert!(clamped.is_owned());
+/// assert_eq!(*clamped, [1, 0, 3, 0]);
+/// ```
+pub enum MiniCow<'a, T> {
+    /// A borrowed value
+    Borrowed(&'a T),
+    /// An owned value
+    Owned(MiniBox<T>),
+}
+
+impl<'a, T> MiniCow<'a, T> {
+    /// Create a new `MiniCow` that borrows `value`
+    #[inline]
+    pub const fn from_borrowed(value: &'a T) -> Self {
+        MiniCow::Borrowed(value)
+    }
+
+    /// Create a new `MiniCow` that owns `value`
+    #[inline]
+    pub fn from_owned(value: T) -> Self {
+        MiniCow::Owned(MiniBox::new(value))
+    }
+
+    /// Returns true if the value is borrowed
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, MiniCow::Borrowed(_))
+    }
+
+    /// Returns true if the value is owned
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Get a mutable reference to the owned value, cloning the borrowed value first if necessary
+    #[inline]
+    pub fn to_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        if let MiniCow::Borrowed(value) = *self {
+            *self = MiniCow::Owned(MiniBox::with(|| value.clone()));
+        }
+
+        match self {
+            MiniCow::Owned(bx) => bx,
+            MiniCow::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Get the owned value, cloning the borrowed value if necessary
+    #[inline]
+    pub fn into_owned(self) -> MiniBox<T>
+    where
+        T: Clone,
+    {
+        match self {
+            MiniCow::Borrowed(value) => MiniBox::with(|| value.clone()),
+            MiniCow::Owned(bx) => bx,
+        }
+    }
+}
+
+impl<T> Deref for MiniCow<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        match self {
+            MiniCow::Borrowed(value) => value,
+            MiniCow::Owned(bx) => bx,
+        }
+    }
+}
+
+impl<T> From<MiniBox<T>> for MiniCow<'_, T> {
+    #[inline]
+    fn from(bx: MiniBox<T>) -> Self {
+        MiniCow::Owned(bx)
+    }
+}
+
+impl<'a, T> From<&'a T> for MiniCow<'a, T> {
+    #[inline]
+    fn from(value: &'a T) -> Self {
+        MiniCow::Borrowed(value)
+    }
+}
+
+impl<T: Clone> Clone for MiniCow<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            MiniCow::Borrowed(value) => MiniCow::Borrowed(value),
+            MiniCow::Owned(bx) => MiniCow::Owned(bx.clone()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MiniCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MiniCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<MiniCow<'_, U>> for MiniCow<'_, T> {
+    #[inline]
+    fn eq(&self, other: &MiniCow<'_, U>) -> bool {
+        T::eq(self, other)
+    }
+}
+
+impl<T: Eq> Eq for MiniCow<'_, T> {}
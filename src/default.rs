@@ -1,30 +1,47 @@
-use super::MiniBox;
+use super::{AllocError, MiniBox};
 
 use core::ptr::NonNull;
 use std::boxed::Box;
 use std::rc::Rc;
 use std::sync::Arc;
 
+// `default fn` is gated before `cfg` is evaluated, so the specializing impls live in a separate file
 #[cfg(feature = "nightly")]
-impl<T: Default> Default for MiniBox<T> {
-    #[inline]
-    default fn default() -> Self {
-        Self::with(T::default)
-    }
-}
-
+mod specialize;
 #[cfg(feature = "nightly")]
-impl<T: Zeroable + Default> Default for MiniBox<T> {
-    fn default() -> Self {
-        Self::zeroed()
-    }
-}
+pub(crate) use specialize::NewBoxed;
 
 impl<T: Zeroable> MiniBox<T> {
     /// intializes a new `MiniBox` with
     pub fn zeroed() -> Self {
         unsafe { Self::new_zeroed().assume_init() }
     }
+
+    /// intializes a new `MiniBox` with zeroes, returning an error if the allocation fails
+    #[inline]
+    pub fn try_zeroed() -> Result<Self, AllocError> {
+        Ok(unsafe { Self::try_new_zeroed()?.assume_init() })
+    }
+}
+
+impl<T: ZeroedDefault> MiniBox<T> {
+    /// Create a new `MiniBox` containing `T::default()`, without writing the value
+    ///
+    /// Because `T::default()` is all zeros, this uses `alloc_zeroed` instead of allocating and
+    /// then writing the value, which for large types skips a redundant memset/memcpy.
+    /// With the `nightly` feature, `MiniBox::<T>::default()` does this automatically, and so do
+    /// `MiniBox::new` and `MiniBox::from` for boxed values that compare equal to zero (if `T` is
+    /// `PartialEq`).
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::<[[u64; 32]; 32]>::zeroed_default();
+    /// assert_eq!(*bx, <[[u64; 32]; 32]>::default());
+    /// ```
+    #[inline]
+    pub fn zeroed_default() -> Self {
+        Self::zeroed()
+    }
 }
 
 /// a safe wrapper of core::mem::zeroed
@@ -32,6 +49,18 @@ pub fn zeroed<T: Zeroable>() -> T {
     unsafe { core::mem::zeroed() }
 }
 
+/// a type whose `Default` value is all zero bytes
+///
+/// This allows `MiniBox::zeroed_default` (and `MiniBox::default`, `MiniBox::new` and
+/// `MiniBox::from` on nightly) to allocate already-zeroed memory instead of writing
+/// `T::default()` into it
+///
+/// # Safety
+///
+/// `T::default()` must be equivalent to `zeroed::<T>()`, i.e. it must have no side
+/// effects, and the value it returns must consist entirely of zero bytes
+pub unsafe trait ZeroedDefault: Zeroable + Default {}
+
 /// a type that is safe to be zeroed
 ///
 /// # Safety
@@ -77,18 +106,18 @@ unsafe impl Zeroable for isize {}
 
 // non-zero
 
-unsafe impl Zeroable for Option<std::num::NonZeroU8> {}
-unsafe impl Zeroable for Option<std::num::NonZeroU16> {}
-unsafe impl Zeroable for Option<std::num::NonZeroU32> {}
-unsafe impl Zeroable for Option<std::num::NonZeroU64> {}
-unsafe impl Zeroable for Option<std::num::NonZeroU128> {}
-unsafe impl Zeroable for Option<std::num::NonZeroUsize> {}
-unsafe impl Zeroable for Option<std::num::NonZeroI8> {}
-unsafe impl Zeroable for Option<std::num::NonZeroI16> {}
-unsafe impl Zeroable for Option<std::num::NonZeroI32> {}
-unsafe impl Zeroable for Option<std::num::NonZeroI64> {}
-unsafe impl Zeroable for Option<std::num::NonZeroI128> {}
-unsafe impl Zeroable for Option<std::num::NonZeroIsize> {}
+unsafe impl Zeroable for Option<core::num::NonZeroU8> {}
+unsafe impl Zeroable for Option<core::num::NonZeroU16> {}
+unsafe impl Zeroable for Option<core::num::NonZeroU32> {}
+unsafe impl Zeroable for Option<core::num::NonZeroU64> {}
+unsafe impl Zeroable for Option<core::num::NonZeroU128> {}
+unsafe impl Zeroable for Option<core::num::NonZeroUsize> {}
+unsafe impl Zeroable for Option<core::num::NonZeroI8> {}
+unsafe impl Zeroable for Option<core::num::NonZeroI16> {}
+unsafe impl Zeroable for Option<core::num::NonZeroI32> {}
+unsafe impl Zeroable for Option<core::num::NonZeroI64> {}
+unsafe impl Zeroable for Option<core::num::NonZeroI128> {}
+unsafe impl Zeroable for Option<core::num::NonZeroIsize> {}
 
 unsafe impl Zeroable for core::sync::atomic::AtomicU8 {}
 unsafe impl Zeroable for core::sync::atomic::AtomicU16 {}
@@ -114,10 +143,58 @@ unsafe impl<T: ?Sized> Zeroable for Option<NonNull<T>> {}
 unsafe impl<T: ?Sized> Zeroable for Option<Box<T>> {}
 unsafe impl<T: ?Sized> Zeroable for Option<Rc<T>> {}
 unsafe impl<T: ?Sized> Zeroable for Option<Arc<T>> {}
-unsafe impl<T> Zeroable for Option<Vec<T>> {}
+unsafe impl<T> Zeroable for Option<std::vec::Vec<T>> {}
 
 tuple! { A B C D E F G H I J K L M N O P }
 
+macro_rules! zeroed_default {
+    ($($type:ty),* $(,)?) => {
+        $(unsafe impl ZeroedDefault for $type {})*
+    };
+}
+
+macro_rules! zeroed_default_tuple {
+    ($($ident:ident)*) => {
+        zeroed_default_tuple!{@next $($ident)*}
+        unsafe impl<$($ident: ZeroedDefault),*> ZeroedDefault for ($($ident,)*) {}
+    };
+
+    (@next ) => {};
+    (@next $first:ident $($ident:ident)*) => {
+        zeroed_default_tuple!{$($ident)*}
+    };
+}
+
+zeroed_default! {
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    core::sync::atomic::AtomicU8,
+    core::sync::atomic::AtomicU16,
+    core::sync::atomic::AtomicU32,
+    core::sync::atomic::AtomicU64,
+    core::sync::atomic::AtomicUsize,
+    core::sync::atomic::AtomicI8,
+    core::sync::atomic::AtomicI16,
+    core::sync::atomic::AtomicI32,
+    core::sync::atomic::AtomicI64,
+    core::sync::atomic::AtomicIsize,
+}
+
+// `None` is guaranteed to be represented as a null pointer
+unsafe impl<T> ZeroedDefault for core::sync::atomic::AtomicPtr<T> {}
+unsafe impl<T: ?Sized> ZeroedDefault for Option<&T> {}
+unsafe impl<T: ?Sized> ZeroedDefault for Option<&mut T> {}
+unsafe impl<T: ?Sized> ZeroedDefault for Option<NonNull<T>> {}
+unsafe impl<T: ?Sized> ZeroedDefault for Option<Box<T>> {}
+
+unsafe impl<T: ZeroedDefault, const N: usize> ZeroedDefault for [T; N] where
+    [T; N]: Zeroable + Default
+{
+}
+
+// `Default` is only implemented for tuples of up to 12 elements
+zeroed_default_tuple! { A B C D E F G H I J K L }
+
 unsafe impl<T> Zeroable for [T; 0] {}
 #[cfg(feature = "nightly")]
 unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
@@ -0,0 +1,42 @@
+use super::{zeroed, ZeroedDefault};
+use crate::MiniBox;
+
+impl<T: Default> Default for MiniBox<T> {
+    #[inline]
+    default fn default() -> Self {
+        Self::with(T::default)
+    }
+}
+
+impl<T: ZeroedDefault> Default for MiniBox<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+/// picks how `MiniBox::new` (and so `MiniBox::from`) allocates a boxed value
+pub(crate) trait NewBoxed: Sized {
+    fn new_boxed(value: Self) -> MiniBox<Self>;
+}
+
+impl<T> NewBoxed for T {
+    #[inline]
+    default fn new_boxed(value: Self) -> MiniBox<Self> {
+        MiniBox::new_uninit().write(value)
+    }
+}
+
+/// if the value is all zeros, use `alloc_zeroed` instead of writing it, when `value` is
+/// `T::default()` the comparison is usually folded away
+impl<T: ZeroedDefault + PartialEq> NewBoxed for T {
+    #[inline]
+    fn new_boxed(value: Self) -> MiniBox<Self> {
+        if value == zeroed() {
+            drop(value);
+            MiniBox::zeroed()
+        } else {
+            MiniBox::new_uninit().write(value)
+        }
+    }
+}
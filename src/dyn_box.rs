@@ -0,0 +1,104 @@
+use super::MiniBox;
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use std::boxed::Box;
+
+/// A one-word box for unsized values, like trait objects
+///
+/// A pointer to an unsized value is two words wide (the data pointer and the vtable or length),
+/// so it can't be stored inline in a `MiniBox`. `DynMiniBox<T>` instead stores the `Box<T>` itself
+/// in a `MiniBox`, so the `DynMiniBox` is still one word wide. This takes two heap allocations:
+/// one for the value, and one for the two-word `Box<T>` that points to it.
+///
+/// Use `DynMiniBox::from_box` (which accepts any `Box` that coerces to `Box<T>`) or the
+/// `dyn_minibox!` macro to create one. With the `nightly` feature, `DynMiniBox::new` unsizes the
+/// value directly.
+///
+/// ```rust
+/// # use minibox::{dyn_minibox, DynMiniBox};
+/// use core::fmt::Display;
+///
+/// let bx: DynMiniBox<dyn Display> = dyn_minibox!(10_u32);
+/// assert_eq!(bx.to_string(), "10");
+///
+/// let bx = DynMiniBox::<dyn Display>::from_box(Box::new("hello"));
+/// assert_eq!(bx.to_string(), "hello");
+///
+/// assert_eq!(core::mem::size_of::<DynMiniBox<dyn Display>>(), core::mem::size_of::<usize>());
+/// ```
+pub struct DynMiniBox<T: ?Sized> {
+    bx: MiniBox<Box<T>>,
+}
+
+/// Create a new `DynMiniBox`, unsizing the value to the expected type
+///
+/// see `DynMiniBox` for details
+#[macro_export]
+macro_rules! dyn_minibox {
+    ($value:expr) => {
+        $crate::DynMiniBox::from_box($crate::__private::Box::new($value))
+    };
+}
+
+impl<T: ?Sized> DynMiniBox<T> {
+    /// Create a new `DynMiniBox<T>` from a `Box<T>`
+    ///
+    /// The value stays in the `Box`'s allocation, but the `Box` itself is moved into a new
+    /// allocation
+    #[inline]
+    pub fn from_box(bx: Box<T>) -> Self {
+        Self {
+            bx: MiniBox::new(bx),
+        }
+    }
+
+    /// Convert the `DynMiniBox<T>` back into a `Box<T>`
+    #[inline]
+    pub fn into_box(bx: Self) -> Box<T> {
+        MiniBox::into_inner(bx.bx)
+    }
+
+    /// Create a new `DynMiniBox<T>` by unsizing `value`
+    #[inline]
+    #[cfg(feature = "nightly")]
+    pub fn new<U: core::marker::Unsize<T>>(value: U) -> Self {
+        let bx: Box<T> = Box::<U>::new(value);
+        Self::from_box(bx)
+    }
+}
+
+impl<T: ?Sized> Deref for DynMiniBox<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.bx
+    }
+}
+
+impl<T: ?Sized> DerefMut for DynMiniBox<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.bx
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for DynMiniBox<T> {
+    #[inline]
+    fn from(bx: Box<T>) -> Self {
+        Self::from_box(bx)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for DynMiniBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for DynMiniBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
@@ -0,0 +1,114 @@
+use super::{MiniBox, SizeClass};
+
+use core::ptr::NonNull;
+
+/// A handle to a heap allocated `MiniBox` value, for building intrusive data structures
+///
+/// Unlike `MiniPtr`, a `MiniBoxHandle` can only be created for `SizeClass::Boxed` types, so the value
+/// has a stable address for as long as the handle exists, and the handle is always a valid non-null pointer.
+/// The handle doesn't own the value, it must be converted back with `MiniBoxHandle::into_box`
+/// (exactly once) to drop the value and free its allocation, otherwise it is leaked
+///
+/// ```rust
+/// # use minibox::{MiniBox, MiniBoxHandle};
+/// struct Node {
+///     value: u64,
+///     next: Option<MiniBoxHandle<Node>>,
+/// }
+///
+/// let tail = MiniBoxHandle::from_box(MiniBox::new(Node { value: 2, next: None }));
+/// let head = MiniBoxHandle::from_box(MiniBox::new(Node { value: 1, next: Some(tail) }));
+///
+/// unsafe {
+///     assert_eq!(head.as_ref().next.unwrap().as_ref().value, 2);
+///
+///     let head = head.into_box();
+///     drop(head.next.unwrap().into_box());
+/// }
+/// ```
+#[repr(transparent)]
+pub struct MiniBoxHandle<T>(NonNull<T>);
+
+impl<T> Copy for MiniBoxHandle<T> {}
+impl<T> Clone for MiniBoxHandle<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for MiniBoxHandle<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for MiniBoxHandle<T> {}
+
+impl<T> core::fmt::Debug for MiniBoxHandle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("MiniBoxHandle").field(&self.0).finish()
+    }
+}
+
+impl<T> MiniBoxHandle<T> {
+    /// Convert a `MiniBox<T>` into a handle, without dropping the value
+    ///
+    /// # Panic
+    ///
+    /// if the `SizeClass` of `T` is not `SizeClass::Boxed`, this function will panic
+    #[inline]
+    pub fn from_box(bx: MiniBox<T>) -> Self {
+        match MiniBox::<T>::SIZE_CLASS {
+            SizeClass::Boxed => unsafe {
+                Self(NonNull::new_unchecked(MiniBox::into_ptr(bx).to_raw()))
+            },
+            _ => panic!("The size class of `T` must be `Boxed`"),
+        }
+    }
+
+    /// Convert the handle back into the `MiniBox<T>` that owns the value
+    ///
+    /// # Safety
+    ///
+    /// The handle (and any copies of it) must not be used after this function
+    #[inline]
+    pub unsafe fn into_box(self) -> MiniBox<T> {
+        MiniBox::from_ptr(crate::MiniPtr::from_raw(self.0.as_ptr()))
+    }
+
+    /// Get the underlying pointer
+    #[inline]
+    pub fn as_ptr(self) -> *mut T {
+        self.0.as_ptr()
+    }
+
+    /// Get the underlying pointer as a `NonNull<T>`
+    #[inline]
+    pub fn as_non_null(self) -> NonNull<T> {
+        self.0
+    }
+
+    /// Get a reference to the underlying value
+    ///
+    /// # Safety
+    ///
+    /// The value must not have been reclaimed with `MiniBoxHandle::into_box`, and must not be
+    /// mutably borrowed for the lifetime `'a`
+    #[inline]
+    pub unsafe fn as_ref<'a>(self) -> &'a T {
+        &*self.0.as_ptr()
+    }
+
+    /// Get a mutable reference to the underlying value
+    ///
+    /// # Safety
+    ///
+    /// The value must not have been reclaimed with `MiniBoxHandle::into_box`, and must not be
+    /// borrowed in any other way for the lifetime `'a`
+    #[inline]
+    pub unsafe fn as_mut<'a>(self) -> &'a mut T {
+        &mut *self.0.as_ptr()
+    }
+}
@@ -13,7 +13,14 @@
         const_transmute,
         specialization,
         const_generics,
-        marker_trait_attr
+        marker_trait_attr,
+        try_trait_v2,
+        error_generic_member_access,
+        auto_traits,
+        negative_impls,
+        clone_to_uninit,
+        unsize,
+        trusted_len
     )
 )]
 
@@ -21,7 +28,7 @@
 //!
 //! a small box implementation that packs the doesn't allocate if the value is layout compatible with
 //! a pointer. (i.e. if it is not larger or more aligned than a pointer). This is almost a drop-in replacement
-//! for `Box<T>` when `T: Sized`. `T: !Sized` is not supported.
+//! for `Box<T>` when `T: Sized`. `T: !Sized` is not supported by `MiniBox`, use `DynMiniBox` instead.
 //!
 //! ```rust
 //! # use minibox::MiniBox;
@@ -92,6 +99,40 @@
 //! // no allocation
 //! let bx = MiniBox::new(Overaligned);
 //! ```
+//!
+//! Pointer-sized types, like references, `NonNull<T>`, and `Box<T>`, are always stored inline. So a
+//! `MiniBox<Box<T>>` only has the allocation of the inner `Box`
+//!
+//! ```rust
+//! # use minibox::{MiniBox, SizeClass};
+//! assert_eq!(MiniBox::<Box<[u8; 1024]>>::SIZE_CLASS, SizeClass::Inline);
+//! assert_eq!(MiniBox::<&str>::SIZE_CLASS, SizeClass::Boxed); // `&str` is two pointers wide
+//! ```
+//!
+//! Uninhabited types (like `core::convert::Infallible`) are zero-sized, so they are never allocated either.
+//! Just like `Box<Infallible>`, a `MiniBox<Infallible>` can never be constructed without `unsafe`,
+//! so any code that has one is unreachable
+//!
+//! ```rust
+//! # use minibox::MiniBox;
+//! use core::convert::Infallible;
+//!
+//! fn parse(s: &str) -> Result<u32, MiniBox<Infallible>> {
+//!     Ok(s.len() as u32)
+//! }
+//!
+//! let len = match parse("hello") {
+//!     Ok(len) => len,
+//!     Err(never) => match *never {},
+//! };
+//!
+//! assert_eq!(len, 5);
+//! ```
+//!
+//! Because inline values live in the `MiniBox` itself, their address is the address of the
+//! `MiniBox`, and it changes whenever the `MiniBox` is moved. So a `Drop` impl (or any other code)
+//! must not rely on a value's address staying the same unless it is boxed or pinned
+//! (see `MiniBox::pin` and `MiniBox::deref_pin_mut`).
 
 #[cfg(not(feature = "std"))]
 extern crate alloc as std;
@@ -101,15 +142,82 @@ use core::mem;
 use core::mem::MaybeUninit;
 use std::boxed::Box;
 
+mod aligned;
+mod builder;
+#[cfg(feature = "bumpalo")]
+mod bump;
+mod call;
+mod cow;
 mod default;
+mod dyn_box;
+mod handle;
+mod nested;
+mod option;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+#[cfg(all(feature = "test-util", feature = "std"))]
+pub mod test_util;
 mod trait_impls;
 
-pub use default::{zeroed, Zeroable};
+pub use aligned::{AlignTo, Aligned, ValidAlign};
+pub use builder::MiniBoxBuilder;
+#[cfg(feature = "bumpalo")]
+pub use bump::BumpMiniBox;
+pub use call::{MiniFn, MiniFnMut, MiniFnOnce};
+pub use cow::MiniCow;
+pub use default::{zeroed, Zeroable, ZeroedDefault};
+pub use dyn_box::DynMiniBox;
+pub use handle::MiniBoxHandle;
+pub use nested::{assert_not_nested, NotMiniBox};
+pub use option::MiniOption;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use core::pin::Pin;
+    pub use std::boxed::Box;
+}
+
+/// Structurally project a `Pin<&mut T>` to pinned references to some of `T`'s fields
+///
+/// Use `bx.as_mut()` to get a `Pin<&mut T>` from a `Pin<MiniBox<T>>`, and
+/// `MiniBox::deref_pin_mut` to get one from a `Pin<&mut MiniBox<T>>`
+///
+/// ```rust
+/// # use minibox::{MiniBox, project_pin};
+/// # use core::pin::Pin;
+/// struct Pair {
+///     a: u64,
+///     b: u64,
+/// }
+///
+/// let mut bx = MiniBox::pin(Pair { a: 1, b: 2 });
+/// let (a, b): (Pin<&mut u64>, Pin<&mut u64>) = unsafe { project_pin!(bx.as_mut() => a, b) };
+/// *a.get_mut() += 10;
+/// *b.get_mut() += 20;
+///
+/// assert_eq!((bx.a, bx.b), (11, 22));
+/// ```
+///
+/// # Safety
+///
+/// This macro must be used in an `unsafe` block. All of the projected fields must be structurally pinned,
+/// i.e. `T` must never move out of these fields while it is pinned (including in its `Drop` impl),
+/// and `T` must not be `#[repr(packed)]`. See the `core::pin` docs for the full requirements.
+#[macro_export]
+macro_rules! project_pin {
+    ($pin:expr => $($field:ident),+ $(,)?) => {{
+        let value = $crate::__private::Pin::get_unchecked_mut($pin);
+        ($($crate::__private::Pin::new_unchecked(&mut value.$field),)+)
+    }};
+}
 
+/// A well aligned pointer for zero-sized `T`s
+///
+/// `NonNull::dangling` doesn't cast an integer to a pointer, so this is fine under strict provenance
 const fn dangling<T>() -> *mut T {
-    core::mem::align_of::<T>() as *mut T
+    core::ptr::NonNull::dangling().as_ptr()
 }
 
 /// A box equivalent that stores the value inline if it is layout compatible with a pointer
@@ -129,7 +237,7 @@ pub struct MiniBox<T> {
 /// * `SizeClass::Zero` - the pointer has no requirements (may even be uninitialized)
 /// * `SizeClass::Inline` - the pointer must be store an initialized `T`
 /// * `SizeClass::Boxed` - the pointer must be store a pointer to a heap
-///     allocated `T` that is allocated with the global allocator
+///   allocated `T` that is allocated with the global allocator
 #[repr(transparent)]
 pub struct MiniPtr<T>(MaybeUninit<*const T>);
 
@@ -141,6 +249,19 @@ impl<T> Clone for MiniPtr<T> {
     }
 }
 
+/// The error returned by the fallible constructors of `MiniBox` when the allocator fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AllocError;
+
+impl core::fmt::Display for AllocError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
 /// The storage strategy of a `MiniBox`/`MiniPtr`
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -152,6 +273,9 @@ pub enum SizeClass {
     /// The value will be stored inline, and `MiniBox` will not allocate
     /// The pointer may contain uninitialized bytes if `T` contains any
     /// `MaybeUninit` or padding bytes
+    ///
+    /// The inline storage is always pointer aligned, so it is suitably aligned for any
+    /// inline `T` (including `#[repr(packed)]` types, which have an alignment of 1)
     Inline = 1,
 
     /// The value is allocated on the heap, and the pointer is guaranteed
@@ -191,8 +315,51 @@ impl SizeClass {
     }
 }
 
+/// The error returned when converting a `u8` that isn't a valid discriminant into a `SizeClass`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InvalidSizeClass;
+
+impl core::fmt::Display for InvalidSizeClass {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("invalid size class")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidSizeClass {}
+
+impl From<SizeClass> for u8 {
+    #[inline]
+    fn from(size_class: SizeClass) -> Self {
+        size_class as u8
+    }
+}
+
+impl core::convert::TryFrom<u8> for SizeClass {
+    type Error = InvalidSizeClass;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SizeClass::Zero),
+            1 => Ok(SizeClass::Inline),
+            2 => Ok(SizeClass::Boxed),
+            _ => Err(InvalidSizeClass),
+        }
+    }
+}
+
+impl PartialEq<u8> for SizeClass {
+    #[inline]
+    fn eq(&self, other: &u8) -> bool {
+        *self as u8 == *other
+    }
+}
+
 impl<T> MiniPtr<T> {
     /// The size class for `T`
+    ///
+    /// see `MiniBox::SIZE_CLASS` for how this is used
     pub const SIZE_CLASS: SizeClass = SizeClass::new::<T>();
 
     /// Create a new uninitialized `MiniPtr`
@@ -205,6 +372,22 @@ impl<T> MiniPtr<T> {
         Self(MaybeUninit::new(raw))
     }
 
+    /// Create a new `MiniPtr` holding a dangling, well aligned pointer
+    ///
+    /// If `T` is zero-sized, this is a valid `MiniPtr` that can be dereferenced, or passed to
+    /// `MiniBox::from_ptr` (which is equivalent to `MiniBox::new_zst`). Otherwise, like
+    /// `NonNull::dangling`, it doesn't point to a value, and it must not be dereferenced
+    ///
+    /// ```rust
+    /// # use minibox::MiniPtr;
+    /// const PTR: MiniPtr<()> = MiniPtr::dangling();
+    /// assert_eq!(unsafe { *PTR.as_ref() }, ());
+    /// ```
+    #[inline]
+    pub const fn dangling() -> Self {
+        Self::from_raw(dangling::<T>())
+    }
+
     /// Get the underlying pointer
     ///
     /// note: this is not a real pointer, so you cannot dereference it. In order to
@@ -227,7 +410,7 @@ impl<T> MiniPtr<T> {
     pub unsafe fn as_ref(&self) -> &T {
         match Self::SIZE_CLASS {
             SizeClass::Zero => &*dangling::<T>(),
-            SizeClass::Inline => &*(self as *const Self as *const T),
+            SizeClass::Inline => &*core::ptr::addr_of!(self.0).cast::<T>(),
             SizeClass::Boxed => &*self.0.assume_init(),
         }
     }
@@ -241,20 +424,152 @@ impl<T> MiniPtr<T> {
     pub unsafe fn as_mut(&mut self) -> &mut T {
         match Self::SIZE_CLASS {
             SizeClass::Zero => &mut *dangling::<T>(),
-            SizeClass::Inline => &mut *(self as *mut Self as *mut T),
+            SizeClass::Inline => &mut *core::ptr::addr_of_mut!(self.0).cast::<T>(),
             SizeClass::Boxed => &mut *(self.0.assume_init() as *mut T),
         }
     }
+
+    /// Convert the `MiniPtr` back into the `MiniBox` that owns the value
+    ///
+    /// This is the inverse of `MiniPtr::from` (or `MiniBox::into_ptr`), and is equivalent to `MiniBox::from_ptr`
+    ///
+    /// ```rust
+    /// # use minibox::{MiniBox, MiniPtr};
+    /// let ptr: MiniPtr<[u64; 4]> = MiniBox::new([1, 2, 3, 4]).into();
+    /// let bx = unsafe { ptr.into_box() };
+    /// assert_eq!(*bx, [1, 2, 3, 4]);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The `MiniPtr` must have come from a `MiniBox<T>`, and must not be used after this function
+    #[inline]
+    pub const unsafe fn into_box(self) -> MiniBox<T> {
+        MiniBox::from_ptr(self)
+    }
+
+    /// Clone the underlying value into a new, owning `MiniBox<T>`
+    ///
+    /// Copying a `MiniPtr` only copies the storage word, so both copies refer to the same value
+    /// (for `SizeClass::Boxed`). This instead clones the value itself, so the new `MiniBox` has its own
+    /// allocation (if any)
+    ///
+    /// # Safety
+    ///
+    /// The safety rules described in the type-level documentation must be followed
+    #[inline]
+    pub unsafe fn clone_deep(&self) -> MiniBox<T>
+    where
+        T: Clone,
+    {
+        MiniBox::new(self.as_ref().clone())
+    }
+
+    /// Get a `Debug` adapter that also shows the raw storage word as hex,
+    /// i.e. `MiniPtr::<[u64; 2]>(Boxed, 0x5581c5f0aba0)`
+    ///
+    /// `MiniPtr`'s own `Debug` impl only shows the size class, because the storage word
+    /// may contain uninitialized bytes
+    ///
+    /// # Safety
+    ///
+    /// the underlying pointer must not contain any uninitialized bytes (see `MiniPtr::to_raw`)
+    #[inline]
+    pub unsafe fn debug_word(&self) -> impl core::fmt::Debug + '_ {
+        struct DebugWord<'a, T>(&'a MiniPtr<T>);
+
+        impl<T> core::fmt::Debug for DebugWord<'_, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "MiniPtr::<{}>({:?}, {:#x})",
+                    core::any::type_name::<T>(),
+                    MiniPtr::<T>::SIZE_CLASS,
+                    unsafe { self.0.to_raw() } as usize
+                )
+            }
+        }
+
+        DebugWord(self)
+    }
 }
 
 impl<T> MiniBox<T> {
     /// The size class for `T`
+    ///
+    /// Every access to the underlying value (`Deref`, `DerefMut`, `Drop`, `into_inner`, ...)
+    /// dispatches on this associated constant. Because it is a constant, the `match` is
+    /// resolved when `MiniBox<T>` is monomorphized, so only the arm for `T`'s size class
+    /// remains after optimization (i.e. accessing an inline value never touches the
+    /// heap deallocation path). These accessors are all `#[inline]` so that this folding
+    /// can happen at the call site.
     pub const SIZE_CLASS: SizeClass = SizeClass::new::<T>();
 
+    /// The size of the stored value, i.e. `size_of::<T>()`
+    ///
+    /// note: this is the size of the value, not of the `MiniBox` (which is always one pointer),
+    /// together with `MiniBox::SIZE_CLASS` this describes where and how the value is stored
+    #[inline]
+    pub const fn stored_size() -> usize {
+        mem::size_of::<T>()
+    }
+
+    /// The alignment of the stored value, i.e. `align_of::<T>()`
+    ///
+    /// see `MiniBox::stored_size` for details
+    #[inline]
+    pub const fn stored_align() -> usize {
+        mem::align_of::<T>()
+    }
+
+    /// Assert that `T` is stored inline (or is zero-sized), i.e. that `MiniBox<T>` never allocates
+    ///
+    /// This is only checked when debug assertions are enabled, and is meant to guard
+    /// tests against accidental heap usage. The panic is reported at the caller's location.
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// MiniBox::<usize>::assert_inline_debug();
+    /// MiniBox::<()>::assert_inline_debug();
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// if debug assertions are enabled and the `SizeClass` of `T` is `SizeClass::Boxed`
+    #[inline]
+    #[track_caller]
+    pub fn assert_inline_debug() {
+        if cfg!(debug_assertions) && matches!(Self::SIZE_CLASS, SizeClass::Boxed) {
+            panic!(
+                "`{}` is not stored inline by `MiniBox`",
+                core::any::type_name::<T>()
+            )
+        }
+    }
+
     /// Create a new `MiniBox<T>`
     #[inline]
     pub fn new(value: T) -> Self {
-        Self::new_uninit().write(value)
+        match Self::SIZE_CLASS {
+            SizeClass::Zero => Self::new_zst(value),
+            // write the value straight into the storage word, instead of going through
+            // `new_uninit`. The word is still zeroed, so that the bytes after the value are
+            // initialized (see `MiniPtr::to_raw`)
+            SizeClass::Inline => {
+                let mut ptr = MaybeUninit::<*const T>::zeroed();
+                unsafe { ptr.as_mut_ptr().cast::<T>().write(value) }
+
+                Self {
+                    ptr,
+                    drop: PhantomData,
+                }
+            }
+            #[cfg(not(feature = "nightly"))]
+            SizeClass::Boxed => Self::new_uninit().write(value),
+            // all-zero `ZeroedDefault` values use `alloc_zeroed` instead
+            #[cfg(feature = "nightly")]
+            SizeClass::Boxed => <T as default::NewBoxed>::new_boxed(value),
+        }
     }
 
     /// Create a new `MiniBox<T>`
@@ -270,19 +585,12 @@ impl<T> MiniBox<T> {
     /// if `T` is not zero-sized, this function will panic
     #[inline]
     pub const fn new_zst(value: T) -> Self {
-        #[cfg(not(feature = "nightly"))]
-        [()][Self::SIZE_CLASS as usize];
-
-        #[cfg(feature = "nightly")]
-        {
-            match Self::SIZE_CLASS {
-                SizeClass::Zero => (),
-                _ => panic!("The size class of `T` must be `Zero`"),
-            }
+        match Self::SIZE_CLASS {
+            SizeClass::Zero => (),
+            _ => panic!("The size class of `T` must be `Zero`"),
         }
 
-        // core::mem::forget is not a const-fn
-        core::mem::ManuallyDrop::new(value);
+        core::mem::forget(value);
 
         Self {
             ptr: MaybeUninit::uninit(),
@@ -306,33 +614,148 @@ impl<T> MiniBox<T> {
         }
     }
 
+    /// Create a new `MiniBox<T>` by initializing the value in place with `f`
+    ///
+    /// This allows large values to be built directly in the `MiniBox`'s storage, instead of
+    /// being built on the stack and then moved in. If `f` panics, the allocation (if any) is freed
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = unsafe {
+    ///     MiniBox::<[u64; 512]>::emplace(|slot| {
+    ///         let ptr = slot.as_mut_ptr().cast::<u64>();
+    ///         for i in 0..512 {
+    ///             ptr.add(i).write(i as u64);
+    ///         }
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(bx[511], 511);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `f` must fully initialize the value it is given (if it returns)
+    #[inline]
+    pub unsafe fn emplace<F: FnOnce(&mut MaybeUninit<T>)>(f: F) -> Self {
+        let mut bx = Self::new_uninit();
+        f(&mut bx);
+        bx.assume_init()
+    }
+
+    /// Create a new uninitialized `MiniBox<T>` without allocating, this can be used to initialize
+    /// `const`s and `static`s
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// # use core::mem::MaybeUninit;
+    /// const SLOT: MiniBox<MaybeUninit<u32>> = MiniBox::<u32>::dangling_uninit();
+    ///
+    /// assert_eq!(*SLOT.write(10), 10);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// if the `SizeClass` of `T` is `SizeClass::Boxed`, this function will panic. In a const context
+    /// this is a compile-time error
+    ///
+    /// ```rust,compile_fail
+    /// # use minibox::MiniBox;
+    /// # use core::mem::MaybeUninit;
+    /// const SLOT: MiniBox<MaybeUninit<[u32; 4]>> = MiniBox::<[u32; 4]>::dangling_uninit();
+    /// ```
+    #[inline]
+    pub const fn dangling_uninit() -> MiniBox<MaybeUninit<T>> {
+        match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => MiniBox {
+                ptr: MaybeUninit::uninit(),
+                drop: PhantomData,
+            },
+            SizeClass::Boxed => panic!("The size class of `T` must not be `Boxed`"),
+        }
+    }
+
     /// Create a new uninitialized `MiniBox<T>`
+    #[inline]
     pub fn new_uninit() -> MiniBox<MaybeUninit<T>> {
         Self::with_alloc(std::alloc::alloc)
     }
 
-    /// Create a new uninitialized `MiniBox<T>`
+    /// Create a new `MiniBox` of `N` uninitialized elements, which can be initialized one at a time
+    ///
+    /// Unlike `MiniBox::<[T; N]>::new_uninit`, which gives a single uninitialized array, each element
+    /// can be written in place. Use `MiniBox::assume_init_array` once every element is initialized.
+    /// If the array is dropped before then, the allocation is freed but none of the elements are dropped
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let mut bx = MiniBox::<String>::new_uninit_array::<3>();
+    /// for (i, slot) in bx.iter_mut().enumerate() {
+    ///     slot.write(i.to_string());
+    /// }
+    /// let bx = unsafe { MiniBox::assume_init_array(bx) };
+    /// assert_eq!(*bx, ["0", "1", "2"]);
+    /// ```
+    #[inline]
+    pub fn new_uninit_array<const N: usize>() -> MiniBox<[MaybeUninit<T>; N]> {
+        // an array of `MaybeUninit` doesn't need to be initialized
+        unsafe { MiniBox::<[MaybeUninit<T>; N]>::new_uninit().assume_init() }
+    }
+
+    /// Create a new zeroed `MiniBox<T>`
+    #[inline]
     pub fn new_zeroed() -> MiniBox<MaybeUninit<T>> {
         Self::with_alloc(std::alloc::alloc_zeroed)
     }
 
+    /// Create a new uninitialized `MiniBox<T>`, returning an error if the allocation fails
+    #[inline]
+    pub fn try_new_uninit() -> Result<MiniBox<MaybeUninit<T>>, AllocError> {
+        Self::try_with_alloc(std::alloc::alloc)
+    }
+
+    /// Create a new zeroed `MiniBox<T>`, returning an error if the allocation fails
+    #[inline]
+    pub fn try_new_zeroed() -> Result<MiniBox<MaybeUninit<T>>, AllocError> {
+        Self::try_with_alloc(std::alloc::alloc_zeroed)
+    }
+
+    /// Create a new `MiniBox<T>`, returning an error if the allocation fails
+    ///
+    /// `value` is dropped if the allocation fails
+    #[inline]
+    pub fn try_new(value: T) -> Result<Self, AllocError> {
+        Ok(Self::try_new_uninit()?.write(value))
+    }
+
     #[inline]
     fn with_alloc(alloc: unsafe fn(std::alloc::Layout) -> *mut u8) -> MiniBox<MaybeUninit<T>> {
+        match Self::try_with_alloc(alloc) {
+            Ok(bx) => bx,
+            Err(AllocError) => std::alloc::handle_alloc_error(std::alloc::Layout::new::<T>()),
+        }
+    }
+
+    #[inline]
+    fn try_with_alloc(
+        alloc: unsafe fn(std::alloc::Layout) -> *mut u8,
+    ) -> Result<MiniBox<MaybeUninit<T>>, AllocError> {
         match Self::SIZE_CLASS {
-            SizeClass::Zero | SizeClass::Inline => Self::new_zeroed_inline(),
+            SizeClass::Zero | SizeClass::Inline => Ok(Self::new_zeroed_inline()),
             SizeClass::Boxed => {
-                use std::alloc::{handle_alloc_error, Layout};
-
-                let layout = Layout::new::<T>();
+                let layout = std::alloc::Layout::new::<T>();
                 let ptr = unsafe { alloc(layout).cast::<MaybeUninit<T>>() };
                 if ptr.is_null() {
-                    handle_alloc_error(layout);
+                    return Err(AllocError);
                 }
 
-                MiniBox {
+                #[cfg(feature = "telemetry")]
+                telemetry::observe(SizeClass::Boxed, layout);
+
+                Ok(MiniBox {
                     ptr: MaybeUninit::new(ptr),
                     drop: PhantomData,
-                }
+                })
             }
         }
     }
@@ -357,235 +780,3455 @@ impl<T> MiniBox<T> {
     #[inline]
     pub const fn into_ptr(bx: Self) -> MiniPtr<T> {
         let ptr = bx.ptr;
-        core::mem::ManuallyDrop::new(bx);
+        core::mem::forget(bx);
         MiniPtr(ptr)
     }
 
-    /// Consume the `MiniBox` returning the underlying data.
-    pub fn into_inner(bx: Self) -> T {
-        unsafe {
-            let ptr = Self::into_ptr(bx);
-            match Self::SIZE_CLASS {
-                SizeClass::Zero => dangling::<T>().read(),
-                SizeClass::Inline => core::ptr::read(ptr.as_ref()),
-                SizeClass::Boxed => *Box::from_raw(ptr.0.assume_init() as *mut T),
-            }
-        }
+    /// Get a reference to the underlying value
+    ///
+    /// This is equivalent to `&*bx`, but it can't be confused with a method on `T`
+    #[inline]
+    pub fn get(bx: &Self) -> &T {
+        bx
     }
 
+    /// Get a mutable reference to the underlying value
+    ///
+    /// This is equivalent to `&mut *bx`, but it can't be confused with a method on `T`
     #[inline]
-    /// project through a `Pin` to get the underlying value
-    pub fn deref_pin_mut(bx: core::pin::Pin<&mut Self>) -> core::pin::Pin<&mut T> {
-        use core::pin::Pin;
-        unsafe { Pin::new_unchecked(Pin::into_inner_unchecked(bx) as &mut T) }
+    pub fn get_mut(bx: &mut Self) -> &mut T {
+        bx
     }
-}
 
-impl<T> MiniBox<MaybeUninit<T>> {
-    /// Consume and initialize the `MiniBox<MaybeUninit<T>>`. This overwrites any previous value without dropping it.
-    /// Returns the initialized `MiniBox<T>`
+    /// Dereference the underlying value, i.e. `MiniBox<String>` to `&str`
+    ///
+    /// This is an associated function so that it doesn't shadow methods of `T`, such as `Option::as_deref`,
+    /// those are still available through `Deref` (i.e. `bx.as_deref()` or `bx.as_str()`)
     #[inline]
-    pub fn write(mut self, value: T) -> MiniBox<T> {
-        unsafe {
-            self.as_mut_ptr().write(value);
-            self.assume_init()
-        }
+    pub fn as_deref(bx: &Self) -> &T::Target
+    where
+        T: core::ops::Deref,
+    {
+        bx
     }
 
-    /// Extracts the value from the `MiniBox<MaybeUninit<T>>` container. This is a great way to ensure
-    /// that the data will get dropped, because the resulting T is subject to the usual drop handling.
+    /// Mutably dereference the underlying value, i.e. `MiniBox<Vec<T>>` to `&mut [T]`
     ///
-    /// # Safety
+    /// see `MiniBox::as_deref` for more information
+    #[inline]
+    pub fn as_deref_mut(bx: &mut Self) -> &mut T::Target
+    where
+        T: core::ops::DerefMut,
+    {
+        bx
+    }
+
+    /// Borrow the underlying value as a `Cow::Borrowed`
+    #[inline]
+    pub fn borrow_cow(bx: &Self) -> std::borrow::Cow<'_, T>
+    where
+        T: Clone,
+    {
+        std::borrow::Cow::Borrowed(bx)
+    }
+
+    /// Write a clone of the underlying value into `dst`
     ///
-    /// It is up to the caller to guarantee that the `MiniBox<MaybeUninit<T>>` really is in an initialized state.
-    /// Calling this when the content is not yet fully initialized causes immediate undefined behavior.
+    /// This is a stable equivalent of `CloneToUninit::clone_to_uninit` (on nightly, `MiniBox<T>`
+    /// implements `CloneToUninit` through std's blanket impl for `T: Clone`)
     ///
-    /// see `MaybeUninit<T>` for more information the initialization invariant
+    /// # Safety
+    ///
+    /// `dst` must be valid for writes and properly aligned. Any value already stored in `dst`
+    /// is overwritten without being dropped
     #[inline]
-    pub unsafe fn assume_init(self) -> MiniBox<T> {
-        mem::transmute(self)
+    pub unsafe fn clone_to(bx: &Self, dst: *mut T)
+    where
+        T: Clone,
+    {
+        dst.write(T::clone(bx))
     }
-}
 
-impl<T> Drop for MiniBox<T> {
-    fn drop(&mut self) {
-        unsafe {
-            match Self::SIZE_CLASS {
-                SizeClass::Zero => dangling::<T>().drop_in_place(),
-                SizeClass::Inline => self.ptr.as_mut_ptr().cast::<T>().drop_in_place(),
-                SizeClass::Boxed => {
-                    dbg!();
-                    drop(Box::from_raw(self.ptr.assume_init() as *mut T))
-                }
+    /// Get a `Debug` adapter that also shows how the value is stored, i.e.
+    /// `MiniBox { size_class: Inline, value: 10 }`
+    ///
+    /// `MiniBox`'s own `Debug` impl only formats the underlying value, so that it can
+    /// be used as a drop-in replacement for `Box`
+    #[inline]
+    pub fn debug_storage(bx: &Self) -> impl core::fmt::Debug + '_
+    where
+        T: core::fmt::Debug,
+    {
+        struct DebugStorage<'a, T>(&'a MiniBox<T>);
+
+        impl<T: core::fmt::Debug> core::fmt::Debug for DebugStorage<'_, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct("MiniBox")
+                    .field("size_class", &MiniBox::<T>::SIZE_CLASS)
+                    .field("value", &**self.0)
+                    .finish()
             }
         }
-    }
-}
 
-impl<T> core::ops::Deref for MiniBox<T> {
-    type Target = T;
+        DebugStorage(bx)
+    }
 
+    /// Get a `Debug` adapter that shows where the value is stored instead of the value itself,
+    /// i.e. `MiniBox(Boxed @ 0x5581c5f0aba0)`
+    ///
+    /// For boxed values this is the storage word (the heap pointer), otherwise it is the address of
+    /// the inline storage. This is useful for types where `Debug` is expensive, recursive,
+    /// or not implemented
     #[inline]
-    fn deref(&self) -> &T {
-        unsafe {
-            match Self::SIZE_CLASS {
-                SizeClass::Zero => &*dangling::<T>(),
-                SizeClass::Inline => &*(self as *const Self as *const T),
-                SizeClass::Boxed => &*self.ptr.assume_init(),
+    pub fn debug_pointer(bx: &Self) -> impl core::fmt::Debug + '_ {
+        struct DebugPointer<'a, T>(&'a MiniBox<T>);
+
+        impl<T> core::fmt::Debug for DebugPointer<'_, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "MiniBox({:?} @ {:p})",
+                    MiniBox::<T>::SIZE_CLASS,
+                    &**self.0 as *const T
+                )
             }
         }
+
+        DebugPointer(bx)
     }
-}
 
-impl<T> core::ops::DerefMut for MiniBox<T> {
+    /// The address of the underlying value, i.e. `&*bx as *const T as usize`
+    ///
+    /// For boxed values this is stable for as long as the value lives, but for inline values
+    /// it is the address of the `MiniBox` itself, so it changes whenever the `MiniBox` is moved
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new([0_u64; 4]);
+    /// let addr = MiniBox::addr(&bx);
+    /// let moved = Some(bx);
+    /// assert_eq!(MiniBox::addr(moved.as_ref().unwrap()), addr);
+    /// ```
     #[inline]
-    fn deref_mut(&mut self) -> &mut T {
-        unsafe {
-            match Self::SIZE_CLASS {
-                SizeClass::Zero => &mut *dangling::<T>(),
-                SizeClass::Inline => &mut *(self as *mut Self as *mut T),
-                SizeClass::Boxed => &mut *(self.ptr.assume_init() as *mut T),
-            }
-        }
+    pub fn addr(bx: &Self) -> usize {
+        &**bx as *const T as usize
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// Compare the underlying values of two `MiniBox`es with a custom comparator
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let mut values = vec![MiniBox::new(3), MiniBox::new(1), MiniBox::new(2)];
+    /// values.sort_by(|a, b| MiniBox::cmp_by(a, b, |a, b| b.cmp(a)));
+    /// assert_eq!(values, [MiniBox::new(3), MiniBox::new(2), MiniBox::new(1)]);
+    /// ```
+    #[inline]
+    pub fn cmp_by<F: FnOnce(&T, &T) -> core::cmp::Ordering>(
+        a: &Self,
+        b: &Self,
+        f: F,
+    ) -> core::cmp::Ordering {
+        f(a, b)
+    }
 
-    #[repr(align(64))]
-    pub struct OverAlignedZeroSized;
-    #[repr(align(64))]
-    pub struct OverAlignedByte(u8);
+    /// Returns `true` if the two `MiniBox`es point to the same value, in a vein similar to `ptr::eq`
+    ///
+    /// * `SizeClass::Zero` - always `true`, all zero-sized values share the same address
+    /// * `SizeClass::Inline` - `true` only if `a` and `b` are the same `MiniBox`, because the value
+    ///   is stored inside of the `MiniBox` itself
+    /// * `SizeClass::Boxed` - `true` if both point to the same heap allocation
+    #[inline]
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        core::ptr::eq::<T>(&**a, &**b)
+    }
 
-    #[test]
-    fn size_class() {
+    /// Copy the storage of the box into a `MiniPtr` without taking ownership of the value
+    ///
+    /// Unlike `MiniBox::into_ptr`, the `MiniBox` still owns the value and will drop it as usual.
+    /// For `SizeClass::Inline`, the `MiniPtr` holds a bitwise copy of the value, so changes made
+    /// through one will not be visible through the other.
+    ///
+    /// # Safety
+    ///
+    /// The returned `MiniPtr` aliases the `MiniBox`, so
+    /// * it must not be used after the `MiniBox` is dropped or moved out of (for `SizeClass::Boxed`,
+    ///   this would be a use-after-free)
+    /// * it must not be passed to `MiniBox::from_ptr` unless the original `MiniBox` is forgotten,
+    ///   otherwise the value will be dropped twice
+    /// * it must not be used to get a mutable reference while the `MiniBox` is borrowed, and vice versa
+    #[inline]
+    pub unsafe fn copy_storage_word(bx: &Self) -> MiniPtr<T> {
+        MiniPtr(bx.ptr)
+    }
+
+    /// Get the bytes of an inline value packed into a `usize`, i.e. to pass it through FFI as an integer
+    ///
+    /// The value occupies the first `size_of::<T>()` bytes of the word in memory order (so the low bytes on
+    /// little-endian targets), and the rest of the word is zero. Use `MiniBox::from_word` to convert it back
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new([1_u8, 2, 3, 4]);
+    /// let word = unsafe { MiniBox::as_word(&bx) };
+    /// let bx = unsafe { MiniBox::<[u8; 4]>::from_word(word) };
+    /// assert_eq!(*bx, [1, 2, 3, 4]);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// if the `SizeClass` of `T` is `SizeClass::Boxed`, this function will panic
+    ///
+    /// # Safety
+    ///
+    /// `T` must not contain any padding or otherwise uninitialized bytes
+    #[inline]
+    pub unsafe fn as_word(bx: &Self) -> usize {
+        if let SizeClass::Boxed = Self::SIZE_CLASS {
+            panic!("The size class of `T` must not be `Boxed`")
+        }
+
+        let mut word = 0_usize;
+        core::ptr::copy_nonoverlapping(
+            &**bx as *const T as *const u8,
+            &mut word as *mut usize as *mut u8,
+            mem::size_of::<T>(),
+        );
+        word
+    }
+
+    /// Create a `MiniBox<T>` from a word created by `MiniBox::as_word`
+    ///
+    /// note: this creates a bitwise copy of the value, so if `T` has a destructor the original
+    /// value should be forgotten
+    ///
+    /// # Panic
+    ///
+    /// if the `SizeClass` of `T` is `SizeClass::Boxed`, this function will panic
+    ///
+    /// # Safety
+    ///
+    /// the first `size_of::<T>()` bytes of `word` (in memory order) must be a valid `T`
+    #[inline]
+    pub unsafe fn from_word(word: usize) -> Self {
+        if let SizeClass::Boxed = Self::SIZE_CLASS {
+            panic!("The size class of `T` must not be `Boxed`")
+        }
+
+        let mut bx = Self::new_uninit();
+        core::ptr::copy_nonoverlapping(
+            &word as *const usize as *const u8,
+            bx.as_mut_ptr() as *mut u8,
+            mem::size_of::<T>(),
+        );
+        bx.assume_init()
+    }
+
+    /// Consume the `MiniBox` returning the underlying data.
+    #[inline]
+    pub fn into_inner(bx: Self) -> T {
+        unsafe {
+            // `into_ptr` forgets `bx`, so after this the returned `T` is the only owner of the value.
+            // Moves can't panic, so there is no point where both could be dropped
+            let ptr = Self::into_ptr(bx);
+            match Self::SIZE_CLASS {
+                SizeClass::Zero => dangling::<T>().read(),
+                SizeClass::Inline => core::ptr::read(ptr.as_ref()),
+                SizeClass::Boxed => *Box::from_raw(ptr.0.assume_init() as *mut T),
+            }
+        }
+    }
+
+    /// Convert the `MiniBox<T>` into a `MiniBox<MaybeUninit<T>>`, reusing its storage without dropping the value
+    ///
+    /// This is the inverse of `MiniBox::assume_init`. The value is still in the storage, so it can
+    /// be moved out with `as_ptr().read()` and the storage reinitialized with `write`. If the returned
+    /// `MiniBox` is dropped, the allocation (if any) is freed, but the value is leaked
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new(String::from("hello"));
+    /// let mut storage = MiniBox::into_uninit(bx);
+    /// let value = unsafe { storage.as_ptr().read() };
+    /// let bx = storage.write(value + " world");
+    /// assert_eq!(*bx, "hello world");
+    /// ```
+    #[inline]
+    pub fn into_uninit(bx: Self) -> MiniBox<MaybeUninit<T>> {
+        // `MaybeUninit<T>` has the same layout as `T`, so it has the same size class
+        unsafe { mem::transmute::<Self, MiniBox<MaybeUninit<T>>>(bx) }
+    }
+
+    /// Consume the `MiniBox` returning the underlying data, along with the allocation that
+    /// held it (if `T` is `SizeClass::Boxed`) so that it can be reused for another `T`
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new([1_u64; 4]);
+    /// let (value, storage) = MiniBox::into_inner_recycle(bx);
+    /// let bx = storage.unwrap().write(value.map(|x| x * 2));
+    /// assert_eq!(*bx, [2; 4]);
+    ///
+    /// let (value, storage) = MiniBox::into_inner_recycle(MiniBox::new(10_u8));
+    /// assert_eq!(value, 10);
+    /// assert!(storage.is_none());
+    /// ```
+    #[inline]
+    pub fn into_inner_recycle(bx: Self) -> (T, Option<MiniBox<MaybeUninit<T>>>) {
+        match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => (Self::into_inner(bx), None),
+            SizeClass::Boxed => {
+                // move the value out, but keep the allocation
+                let storage = Self::into_uninit(bx);
+                let value = unsafe { storage.as_ptr().read() };
+                (value, Some(storage))
+            }
+        }
+    }
+
+    /// Move the underlying value into an `Arc<T>`
+    ///
+    /// note: this always allocates, even if `T` is already heap allocated. The layout of the
+    /// allocation backing an `Arc` (which stores the reference counts next to the value) is
+    /// private to `std`, so a `MiniBox`'s allocation can't be reused for it
+    #[inline]
+    pub fn into_arc(bx: Self) -> std::sync::Arc<T> {
+        std::sync::Arc::new(Self::into_inner(bx))
+    }
+
+    /// Move the underlying value into an `Rc<T>`
+    ///
+    /// note: this always allocates, see `MiniBox::into_arc` for details
+    #[inline]
+    pub fn into_rc(bx: Self) -> std::rc::Rc<T> {
+        std::rc::Rc::new(Self::into_inner(bx))
+    }
+
+    /// Transform the underlying value with `f`
+    ///
+    /// The value is moved out of the `MiniBox` (freeing its allocation, if any) before `f` is called
+    #[inline]
+    pub fn map<U, F: FnOnce(T) -> U>(bx: Self, f: F) -> MiniBox<U> {
+        MiniBox::new(f(Self::into_inner(bx)))
+    }
+
+    /// Replace the underlying value with `f(value)`, in place
+    ///
+    /// The value is moved into `f` and the result is written back into the same storage, so
+    /// a `SizeClass::Boxed` value keeps its allocation (and address)
+    ///
+    /// # Abort
+    ///
+    /// While `f` runs the `MiniBox` doesn't contain a value, so if `f` panics there is nothing
+    /// the `MiniBox` could drop or hand out safely. In that case the process is aborted.
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let mut bx = MiniBox::new([1_u64, 2, 3, 4]);
+    /// let addr = &*bx as *const [u64; 4];
+    ///
+    /// MiniBox::replace_with(&mut bx, |[a, b, c, d]| [d, c, b, a]);
+    ///
+    /// assert_eq!(*bx, [4, 3, 2, 1]);
+    /// assert_eq!(&*bx as *const [u64; 4], addr);
+    /// ```
+    pub fn replace_with<F: FnOnce(T) -> T>(bx: &mut Self, f: F) {
+        struct AbortOnUnwind;
+
+        impl Drop for AbortOnUnwind {
+            fn drop(&mut self) {
+                // panicking while already unwinding aborts the process
+                panic!("the function passed to `MiniBox::replace_with` panicked, aborting")
+            }
+        }
+
+        let ptr: *mut T = &mut **bx;
+        let guard = AbortOnUnwind;
+
+        unsafe { ptr.write(f(ptr.read())) }
+
+        mem::forget(guard);
+    }
+
+    /// Transform the underlying value with the fallible function `f`, propagating the error
+    ///
+    /// The value is moved out of the `MiniBox` (freeing its allocation, if any) before `f` is called,
+    /// so nothing is leaked if `f` fails
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new("42");
+    /// let bx = MiniBox::try_map(bx, str::parse::<u32>).unwrap();
+    /// assert_eq!(*bx, 42);
+    ///
+    /// let bx = MiniBox::new("nope");
+    /// assert!(MiniBox::try_map(bx, str::parse::<u32>).is_err());
+    /// ```
+    #[inline]
+    pub fn try_map<U, E, F: FnOnce(T) -> Result<U, E>>(bx: Self, f: F) -> Result<MiniBox<U>, E> {
+        f(Self::into_inner(bx)).map(MiniBox::new)
+    }
+
+    /// Transform the underlying value with `f`, reusing the allocation if possible
+    ///
+    /// If both `T` and `U` are `SizeClass::Boxed` and have the same alignment, then the allocation is
+    /// resized in place with `realloc` instead of allocating a new one. If `f` panics, the
+    /// allocation is freed.
+    pub fn map_realloc<U, F: FnOnce(T) -> U>(bx: Self, f: F) -> MiniBox<U> {
+        match (Self::SIZE_CLASS, MiniBox::<U>::SIZE_CLASS) {
+            (SizeClass::Boxed, SizeClass::Boxed)
+                if mem::align_of::<T>() == mem::align_of::<U>() =>
+            {
+                // move the value out, but keep the allocation
+                let storage = Self::into_uninit(bx);
+                let value = f(unsafe { storage.as_ptr().read() });
+                MiniBox::realloc_as::<U>(storage).write(value)
+            }
+            _ => MiniBox::new(f(Self::into_inner(bx))),
+        }
+    }
+
+    /// Reinterpret the storage of the `MiniBox<T>` as storage for a `U`, without dropping the `T`
+    ///
+    /// The first `min(size_of::<T>(), size_of::<U>())` bytes of the value are preserved, and the rest
+    /// are uninitialized. If both `T` and `U` are `SizeClass::Boxed` and have the same alignment, then the
+    /// allocation is resized in place with `realloc` instead of allocating a new one and copying the bytes over
+    pub fn realloc_as<U>(bx: Self) -> MiniBox<MaybeUninit<U>> {
+        use std::alloc::{handle_alloc_error, realloc, Layout};
+
+        match (Self::SIZE_CLASS, MiniBox::<U>::SIZE_CLASS) {
+            (SizeClass::Boxed, SizeClass::Boxed)
+                if mem::align_of::<T>() == mem::align_of::<U>() =>
+            {
+                let new_layout = Layout::new::<U>();
+
+                unsafe {
+                    let ptr = Self::into_ptr(bx).to_raw().cast::<u8>();
+                    let ptr = realloc(ptr, Layout::new::<T>(), new_layout.size());
+
+                    if ptr.is_null() {
+                        handle_alloc_error(new_layout)
+                    }
+
+                    #[cfg(feature = "telemetry")]
+                    telemetry::observe(SizeClass::Boxed, new_layout);
+
+                    MiniBox {
+                        ptr: MaybeUninit::new(ptr.cast()),
+                        drop: PhantomData,
+                    }
+                }
+            }
+            _ => {
+                let mut new = MiniBox::<U>::new_uninit();
+                let size = mem::size_of::<T>().min(mem::size_of::<U>());
+
+                unsafe {
+                    let src = &*bx as *const T as *const u8;
+                    core::ptr::copy_nonoverlapping(src, new.as_mut_ptr().cast::<u8>(), size);
+
+                    // free the old allocation (if any) without dropping the value
+                    drop(Self::into_uninit(bx));
+                }
+
+                new
+            }
+        }
+    }
+
+    #[inline]
+    /// project through a `Pin` to get the underlying value
+    ///
+    /// This is sound for every size class: if `T` is stored inline, then pinning the
+    /// `MiniBox` also pins the value, because `MiniBox<T>` is only `Unpin` if `T` is.
+    /// The value may move before the `MiniBox` is pinned (e.g. in `MiniBox::new`), but that
+    /// is fine, because `Pin` only promises that it won't move after it is pinned
+    ///
+    /// So a pinned `MiniBox` holding an inline `!Unpin` value can't be moved out of its `Pin`
+    ///
+    /// ```rust,compile_fail
+    /// # use minibox::MiniBox;
+    /// # use core::pin::{pin, Pin};
+    /// let bx = pin!(MiniBox::new(core::marker::PhantomPinned));
+    /// let bx: MiniBox<_> = core::mem::replace(Pin::into_inner(bx), MiniBox::new(core::marker::PhantomPinned));
+    /// ```
+    pub fn deref_pin_mut(bx: core::pin::Pin<&mut Self>) -> core::pin::Pin<&mut T> {
+        use core::pin::Pin;
+        unsafe { Pin::new_unchecked(Pin::into_inner_unchecked(bx) as &mut T) }
+    }
+
+    /// Project through a pinned `MiniBox` to a field (or any other place) of the underlying value
+    ///
+    /// This is `Pin::map_unchecked_mut` applied to `MiniBox::deref_pin_mut`, and works for every size class.
+    /// A `Pin<MiniBox<T>>` (from `MiniBox::pin`) already derefs to a `Pin<&mut T>` with `Pin::as_mut`,
+    /// so use `Pin::map_unchecked_mut` on that directly. Use the `project_pin!` macro to project to
+    /// several fields at once
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// # use core::pin::{pin, Pin};
+    /// struct Timer {
+    ///     ticks: u32,
+    ///     deadline: u32,
+    /// }
+    ///
+    /// let bx: Pin<&mut MiniBox<Timer>> = pin!(MiniBox::new(Timer { ticks: 0, deadline: 10 }));
+    /// let deadline: Pin<&mut u32> =
+    ///     unsafe { MiniBox::map_unchecked_pin(bx, |timer| &mut timer.deadline) };
+    /// assert_eq!(*deadline, 10);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The same rules as `Pin::map_unchecked_mut` apply:
+    ///
+    /// * `f` must return a reference into the value (usually a field), which doesn't move as long as the value doesn't
+    /// * the projected place must be structurally pinned: the value must never move out of it, or hand out
+    ///   an unpinned `&mut` to it, and `T`'s `Drop` impl must not move it
+    /// * `f` must not move the value it is given
+    #[inline]
+    pub unsafe fn map_unchecked_pin<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(
+        bx: core::pin::Pin<&mut Self>,
+        f: F,
+    ) -> core::pin::Pin<&mut U> {
+        Self::deref_pin_mut(bx).map_unchecked_mut(f)
+    }
+
+    /// Create a new `Pin<MiniBox<T>>`
+    ///
+    /// If `T: Unpin`, use `Pin::new(MiniBox::new(value))` instead, which works for all size classes
+    ///
+    /// # Panic
+    ///
+    /// if the `SizeClass` of `T` is `SizeClass::Inline`, this function will panic.
+    /// Inline values are stored in the `MiniBox` itself, so they would move whenever
+    /// the `Pin<MiniBox<T>>` is moved. To pin an inline value, pin the `MiniBox` itself
+    /// (i.e. get a `Pin<&mut MiniBox<T>>`) and project with `MiniBox::deref_pin_mut`
+    #[inline]
+    pub fn pin(value: T) -> core::pin::Pin<Self> {
+        match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Boxed => (),
+            SizeClass::Inline => panic!("`MiniBox::pin` can't pin values that are stored inline"),
+        }
+
+        // the value is either zero-sized or on the heap, so it never moves
+        unsafe { core::pin::Pin::new_unchecked(Self::new(value)) }
+    }
+}
+
+impl<T> MiniBox<MaybeUninit<T>> {
+    /// Consume and initialize the `MiniBox<MaybeUninit<T>>`. This overwrites any previous value without dropping it.
+    /// Returns the initialized `MiniBox<T>`
+    #[inline]
+    pub fn write(mut self, value: T) -> MiniBox<T> {
+        unsafe {
+            self.as_mut_ptr().write(value);
+            self.assume_init()
+        }
+    }
+
+    /// Consume and initialize the `MiniBox<MaybeUninit<T>>` with `T::default()`.
+    /// This overwrites any previous value without dropping it.
+    ///
+    /// note: this always writes the value, even if the storage came from `new_zeroed`.
+    /// Use `MiniBox::zeroed_default` to skip the write for `ZeroedDefault` types
+    #[inline]
+    pub fn write_default(self) -> MiniBox<T>
+    where
+        T: Default,
+    {
+        self.write(T::default())
+    }
+
+    /// Extracts the value from the `MiniBox<MaybeUninit<T>>` container. This is a great way to ensure
+    /// that the data will get dropped, because the resulting T is subject to the usual drop handling.
+    ///
+    /// # Safety
+    ///
+    /// It is up to the caller to guarantee that the `MiniBox<MaybeUninit<T>>` really is in an initialized state.
+    /// Calling this when the content is not yet fully initialized causes immediate undefined behavior.
+    ///
+    /// see `MaybeUninit<T>` for more information the initialization invariant
+    #[inline]
+    pub unsafe fn assume_init(self) -> MiniBox<T> {
+        mem::transmute(self)
+    }
+}
+
+impl<T> MiniBox<MiniBox<T>> {
+    /// Remove one layer of boxing
+    ///
+    /// A `MiniBox<T>` is always pointer sized, so the inner box is stored inline in the outer one.
+    /// This just moves the inner box out, reusing its storage (and allocation, if any) without
+    /// copying or dropping the underlying value
+    ///
+    /// Nesting a `MiniBox` in another one is rarely intended, since the outer box only adds a word.
+    /// Bound generic code on `NotMiniBox` to catch it (with the `nightly` feature), and use this to
+    /// collapse it
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::new(MiniBox::new([1_u64; 4]));
+    /// let addr = &**bx as *const [u64; 4];
+    ///
+    /// let bx: MiniBox<[u64; 4]> = MiniBox::flatten(bx);
+    /// assert_eq!(&*bx as *const [u64; 4], addr);
+    /// ```
+    #[inline]
+    pub fn flatten(bx: Self) -> MiniBox<T> {
+        Self::into_inner(bx)
+    }
+}
+
+impl MiniBox<()> {
+    /// A `MiniBox<()>`, usable in const contexts
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// const UNIT: MiniBox<()> = MiniBox::UNIT;
+    /// assert_eq!(UNIT, MiniBox::from(()));
+    /// ```
+    pub const UNIT: Self = Self::new_zst(());
+}
+
+macro_rules! total_cmp {
+    ($($float:ident)*) => {$(
+        impl MiniBox<$float> {
+            #[doc = concat!("Compare two `MiniBox<", stringify!($float), ">`s with `", stringify!($float), "::total_cmp`")]
+            ///
+            /// `MiniBox` forwards `PartialOrd` to the underlying value, so NaNs are unordered.
+            /// This gives a total ordering instead, which can be used with `sort_by`
+            #[inline]
+            pub fn total_cmp(a: &Self, b: &Self) -> core::cmp::Ordering {
+                $float::total_cmp(a, b)
+            }
+        }
+    )*};
+}
+
+total_cmp! { f32 f64 }
+
+impl<T> MiniBox<std::vec::Vec<T>> {
+    /// Create a new `MiniBox<Vec<T>>` with at least the given capacity, returning an error instead
+    /// of aborting if the `Vec`'s buffer couldn't be allocated
+    ///
+    /// note: the `Vec` itself is larger than a pointer, so it is always heap allocated. This allocation
+    /// is small, and is still infallible
+    pub fn try_new_with_capacity(
+        capacity: usize,
+    ) -> Result<Self, std::collections::TryReserveError> {
+        let mut vec = std::vec::Vec::new();
+        vec.try_reserve_exact(capacity)?;
+        Ok(Self::new(vec))
+    }
+
+    /// Convert the `Vec<T>` into a `Box<[T]>`, dropping any excess capacity
+    ///
+    /// The `Vec`'s buffer is reused (and only reallocated if it has excess capacity)
+    #[inline]
+    pub fn into_boxed_slice(bx: Self) -> MiniBox<Box<[T]>> {
+        MiniBox::new(Self::into_inner(bx).into_boxed_slice())
+    }
+}
+
+impl<T, const N: usize> MiniBox<[T; N]> {
+    /// Create a new `MiniBox<[T; N]>` where each element is initialized by calling `f` with its index
+    ///
+    /// The elements are written directly into the `MiniBox`'s storage, so large arrays are never
+    /// built on the stack. If `f` panics, all of the elements that were already initialized are dropped,
+    /// and the allocation (if any) is freed
+    pub fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        let mut bx = MiniBox::<[T; N]>::new_uninit();
+        let mut guard = ArrayGuard {
+            ptr: bx.as_mut_ptr().cast::<T>(),
+            len: 0,
+        };
+
+        while guard.len < N {
+            unsafe { guard.ptr.add(guard.len).write(f(guard.len)) }
+            guard.len += 1;
+        }
+
+        mem::forget(guard);
+        unsafe { bx.assume_init() }
+    }
+
+    /// Leak the array, returning a mutable slice of its elements that lives as long as `'a`
+    ///
+    /// Inline arrays are moved to the heap first, so that the slice has a stable address. The
+    /// elements are never dropped, unless the slice is turned back into a `Box<[T]>` with
+    /// `Box::from_raw`, which is allowed for every size class: the slice is always allocated by
+    /// the global allocator with the layout of `[T; N]`, just like a `Box<[T]>` of length `N`
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let slice: &'static mut [u32] = MiniBox::leak_as_slice(MiniBox::new([1, 2, 3, 4]));
+    /// slice[0] = 10;
+    /// assert_eq!(slice, [10, 2, 3, 4]);
+    ///
+    /// // reclaim the allocation
+    /// let bx: Box<[u32]> = unsafe { Box::from_raw(slice) };
+    /// assert_eq!(*bx, [10, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn leak_as_slice<'a>(bx: Self) -> &'a mut [T]
+    where
+        T: 'a,
+    {
+        match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => Box::leak(Box::new(Self::into_inner(bx))),
+            SizeClass::Boxed => unsafe { &mut *Self::into_ptr(bx).to_raw() },
+        }
+    }
+
+    /// Convert the array into a `Vec<T>` with a length and capacity of `N`
+    ///
+    /// If the array is `SizeClass::Boxed`, its allocation is reused as the `Vec`'s buffer (the
+    /// layouts are identical), otherwise the elements are moved into a new `Vec`
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let mut vec = MiniBox::into_vec(MiniBox::new([1_u64, 2, 3]));
+    /// vec.push(4);
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn into_vec(bx: Self) -> std::vec::Vec<T> {
+        match Self::SIZE_CLASS {
+            SizeClass::Zero | SizeClass::Inline => std::vec::Vec::from(Self::into_inner(bx)),
+            SizeClass::Boxed => {
+                let array = unsafe { Box::from_raw(Self::into_ptr(bx).to_raw()) };
+                let slice: Box<[T]> = array;
+                slice.into_vec()
+            }
+        }
+    }
+
+    /// Clone the array element by element, directly into the new `MiniBox`'s storage
+    ///
+    /// Unlike `Clone::clone`, this never builds a copy of the array on the stack, so it is
+    /// suitable for large arrays. If cloning an element panics, all of the elements that were
+    /// already cloned are dropped and the new allocation (if any) is freed.
+    ///
+    /// `Clone::clone` does *not* do this on stable: it clones the whole array on the stack and
+    /// then moves it into the new `MiniBox`, because the generic `Clone` impl can't tell that `T`
+    /// is an array without specialization. Call this function directly to clone large arrays.
+    /// (The `nightly` feature specializes `Clone` to use this, but that feature does not currently
+    /// build)
+    #[inline]
+    pub fn clone_array(bx: &Self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(|i| bx[i].clone())
+    }
+}
+
+impl<T: Copy, const N: usize> MiniBox<[T; N]> {
+    /// Create a new `MiniBox<[T; N]>` by copying the elements of `slice` directly into the
+    /// `MiniBox`'s storage, so large arrays are never built on the stack
+    ///
+    /// Returns `None` if the length of `slice` is not `N`
+    pub fn try_from_slice(slice: &[T]) -> Option<Self> {
+        if slice.len() != N {
+            return None;
+        }
+
+        let mut bx = MiniBox::<[T; N]>::new_uninit();
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(slice.as_ptr(), bx.as_mut_ptr().cast::<T>(), N);
+            Some(bx.assume_init())
+        }
+    }
+
+    /// Create a new `MiniBox<[T; N]>` by copying the elements of `slice` directly into the
+    /// `MiniBox`'s storage, see `MiniBox::try_from_slice` for details
+    ///
+    /// ```rust
+    /// # use minibox::MiniBox;
+    /// let bx = MiniBox::<[u32; 3]>::from_copy_slice(&[1, 2, 3]);
+    /// assert_eq!(*bx, [1, 2, 3]);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// if the length of `slice` is not `N`, this function will panic
+    #[inline]
+    #[track_caller]
+    pub fn from_copy_slice(slice: &[T]) -> Self {
+        match Self::try_from_slice(slice) {
+            Some(bx) => bx,
+            None => panic!(
+                "source slice length ({}) does not match destination array length ({})",
+                slice.len(),
+                N
+            ),
+        }
+    }
+}
+
+impl<T, const N: usize> MiniBox<[MaybeUninit<T>; N]> {
+    /// Convert a `MiniBox` of initialized elements into a `MiniBox<[T; N]>`, reusing the storage
+    ///
+    /// # Safety
+    ///
+    /// Every element of the array must be initialized
+    #[inline]
+    pub unsafe fn assume_init_array(bx: Self) -> MiniBox<[T; N]> {
+        // `[MaybeUninit<T>; N]` has the same layout as `[T; N]`, so they have the same size class
+        mem::transmute::<Self, MiniBox<[T; N]>>(bx)
+    }
+}
+
+/// Collect exactly `N` items from `iter` into a `MiniBox<[T; N]>`
+///
+/// The items are written directly into the `MiniBox`'s storage, so large arrays are never built
+/// on the stack. Returns `None` if `iter` yields fewer or more than `N` items, in which case all of
+/// the items that were taken from `iter` are dropped
+///
+/// ```rust
+/// # use minibox::{collect_array, MiniBox};
+/// let bx: MiniBox<[u32; 4]> = collect_array((1..=4).map(|x| x * x)).unwrap();
+/// assert_eq!(*bx, [1, 4, 9, 16]);
+///
+/// assert!(collect_array::<u32, _, 4>(1..4).is_none());
+/// assert!(collect_array::<u32, _, 4>(1..6).is_none());
+/// ```
+pub fn collect_array<T, I: IntoIterator<Item = T>, const N: usize>(
+    iter: I,
+) -> Option<MiniBox<[T; N]>> {
+    let mut iter = iter.into_iter();
+    let mut bx = MiniBox::<[T; N]>::new_uninit();
+    let mut guard = ArrayGuard {
+        ptr: bx.as_mut_ptr().cast::<T>(),
+        len: 0,
+    };
+
+    while guard.len < N {
+        let item = iter.next()?;
+        unsafe { guard.ptr.add(guard.len).write(item) }
+        guard.len += 1;
+    }
+
+    if iter.next().is_some() {
+        return None;
+    }
+
+    mem::forget(guard);
+    Some(unsafe { bx.assume_init() })
+}
+
+/// Drops the first `len` elements of the array starting at `ptr`, used to clean up a partially
+/// initialized array if a panic occurs while initializing it
+struct ArrayGuard<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+impl<T> Drop for ArrayGuard<T> {
+    fn drop(&mut self) {
+        unsafe { core::ptr::slice_from_raw_parts_mut(self.ptr, self.len).drop_in_place() }
+    }
+}
+
+// The `match` below folds away even without optimizations: for an inline `T`,
+// `<MiniBox<T> as Drop>::drop` compiles to no branches or calls at all (check with
+// `cargo rustc -- --emit=asm` on a crate that drops a `MiniBox<u32>`)
+impl<T> Drop for MiniBox<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            match Self::SIZE_CLASS {
+                SizeClass::Zero => dangling::<T>().drop_in_place(),
+                SizeClass::Inline => core::ptr::addr_of_mut!(self.ptr)
+                    .cast::<T>()
+                    .drop_in_place(),
+                SizeClass::Boxed => drop(Box::from_raw(self.ptr.assume_init() as *mut T)),
+            }
+        }
+    }
+}
+
+impl<T> core::ops::Deref for MiniBox<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe {
+            match Self::SIZE_CLASS {
+                SizeClass::Zero => &*dangling::<T>(),
+                // derive the pointer directly from the storage field with `addr_of!`, instead of
+                // casting a reference to the whole `MiniBox`, so that no intermediate reference is created
+                SizeClass::Inline => &*core::ptr::addr_of!(self.ptr).cast::<T>(),
+                SizeClass::Boxed => &*self.ptr.assume_init(),
+            }
+        }
+    }
+}
+
+impl<T> core::ops::DerefMut for MiniBox<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe {
+            match Self::SIZE_CLASS {
+                SizeClass::Zero => &mut *dangling::<T>(),
+                SizeClass::Inline => &mut *core::ptr::addr_of_mut!(self.ptr).cast::<T>(),
+                SizeClass::Boxed => &mut *(self.ptr.assume_init() as *mut T),
+            }
+        }
+    }
+}
+
+// these tests use `std` APIs such as `std::panic` and `std::thread`
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[repr(align(64))]
+    pub struct OverAlignedZeroSized;
+    #[repr(align(64))]
+    pub struct OverAlignedByte(u8);
+
+    #[test]
+    fn size_class() {
         assert!(matches!(SizeClass::new::<()>(), SizeClass::Zero));
         assert!(matches!(
             SizeClass::new::<OverAlignedZeroSized>(),
             SizeClass::Zero
         ));
 
-        assert!(matches!(SizeClass::new::<u8>(), SizeClass::Inline));
-        assert!(matches!(
-            SizeClass::new::<OverAlignedByte>(),
-            SizeClass::Boxed
-        ));
+        assert!(matches!(SizeClass::new::<u8>(), SizeClass::Inline));
+        assert!(matches!(
+            SizeClass::new::<OverAlignedByte>(),
+            SizeClass::Boxed
+        ));
+
+        assert!(matches!(SizeClass::new::<usize>(), SizeClass::Inline));
+        assert!(matches!(
+            SizeClass::new::<Box<[u8; 64]>>(),
+            SizeClass::Inline
+        ));
+        assert!(matches!(SizeClass::new::<&[u8; 64]>(), SizeClass::Inline));
+        assert!(matches!(
+            SizeClass::new::<core::ptr::NonNull<u8>>(),
+            SizeClass::Inline
+        ));
+        assert!(matches!(SizeClass::new::<[usize; 2]>(), SizeClass::Boxed));
+    }
+
+    #[test]
+    fn size_class_is_const() {
+        const ZERO: SizeClass = MiniBox::<()>::SIZE_CLASS;
+        const INLINE: SizeClass = MiniBox::<u8>::SIZE_CLASS;
+        const BOXED: SizeClass = MiniBox::<[usize; 2]>::SIZE_CLASS;
+
+        assert_eq!(ZERO, SizeClass::Zero);
+        assert_eq!(INLINE, SizeClass::Inline);
+        assert_eq!(BOXED, SizeClass::Boxed);
+
+        assert_eq!(MiniPtr::<u8>::SIZE_CLASS, MiniBox::<u8>::SIZE_CLASS);
+    }
+
+    #[test]
+    fn stored_layout() {
+        use core::mem::{align_of, size_of};
+
+        fn check<T>() {
+            assert_eq!(MiniBox::<T>::stored_size(), size_of::<T>());
+            assert_eq!(MiniBox::<T>::stored_align(), align_of::<T>());
+            assert_eq!(size_of::<MiniBox<T>>(), size_of::<usize>());
+        }
+
+        check::<()>();
+        check::<OverAlignedZeroSized>();
+        check::<u8>();
+        check::<OverAlignedByte>();
+        check::<usize>();
+        check::<[usize; 2]>();
+        check::<[u8; 3]>();
+        check::<std::string::String>();
+
+        const SIZE: usize = MiniBox::<[u16; 3]>::stored_size();
+        const ALIGN: usize = MiniBox::<[u16; 3]>::stored_align();
+        assert_eq!((SIZE, ALIGN), (6, 2));
+        assert_eq!(MiniBox::<OverAlignedByte>::stored_align(), 64);
+    }
+
+    #[test]
+    pub fn not_nested() {
+        assert_not_nested::<u8>();
+        assert_not_nested::<[u64; 4]>();
+        assert_not_nested::<Box<u8>>();
+    }
+
+    #[test]
+    #[cfg(not(feature = "nightly"))]
+    pub fn nested_is_only_a_marker_on_stable() {
+        fn store<T: crate::NotMiniBox>(value: T) -> MiniBox<T> {
+            MiniBox::new(value)
+        }
+
+        // on stable the guard accepts every type, including a `MiniBox`
+        assert_not_nested::<MiniBox<MiniBox<u8>>>();
+        let bx = store(MiniBox::new(0_u8));
+        assert_eq!(**bx, 0);
+        assert_eq!(*store(10_u32), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(not(miri))]
+    pub fn nonzerosized_const() {
+        MiniBox::new_zst(0);
+    }
+
+    #[test]
+    pub fn zst() {
+        let bx = MiniBox::new_zst(());
+        assert_eq!(*bx, ());
+
+        let bx = MiniBox::new_zst(OverAlignedZeroSized);
+        assert!(matches!(*bx, OverAlignedZeroSized));
+    }
+
+    #[test]
+    pub fn overaligned() {
+        let bx = MiniBox::new(OverAlignedByte(3));
+        assert_eq!(bx.0, 3);
+    }
+
+    #[test]
+    pub fn boxed() {
+        let bx = MiniBox::new([3_u8; 32]);
+        assert_eq!(*bx, [3; 32]);
+
+        let bx = MiniBox::<[u8; 32]>::zeroed();
+        assert_eq!(*bx, [0; 32]);
+    }
+
+    #[test]
+    pub fn inline() {
+        let bx = MiniBox::new(3_u8);
+        assert_eq!(*bx, 3);
+
+        let bx = MiniBox::<u8>::zeroed();
+        assert_eq!(*bx, 0);
+    }
+
+    #[test]
+    pub fn word() {
+        let bx = MiniBox::new(3_usize);
+        assert_eq!(*bx, 3);
+
+        let bx = MiniBox::<usize>::zeroed();
+        assert_eq!(*bx, 0);
+    }
+
+    #[test]
+    fn from_fn() {
+        let bx = MiniBox::<[u8; 4]>::from_fn(|i| i as u8 * 2);
+        assert_eq!(MiniBox::<[u8; 4]>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(*bx, [0, 2, 4, 6]);
+
+        let bx = MiniBox::<[usize; 64]>::from_fn(|i| i);
+        assert_eq!(MiniBox::<[usize; 64]>::SIZE_CLASS, SizeClass::Boxed);
+        assert!(bx.iter().enumerate().all(|(i, &x)| i == x));
+
+        let bx = MiniBox::<[(); 3]>::from_fn(|_| ());
+        assert_eq!(*bx, [(); 3]);
+    }
+
+    #[test]
+    fn ptr_eq() {
+        let a = MiniBox::new([1_u64; 4]);
+        let b = MiniBox::new([1_u64; 4]);
+        assert_eq!(a, b);
+        assert!(MiniBox::ptr_eq(&a, &a));
+        assert!(!MiniBox::ptr_eq(&a, &b));
+
+        let a = MiniBox::new(1_u8);
+        let b = MiniBox::new(1_u8);
+        assert!(MiniBox::ptr_eq(&a, &a));
+        assert!(!MiniBox::ptr_eq(&a, &b));
+
+        assert!(MiniBox::ptr_eq(&MiniBox::new(()), &MiniBox::new(())));
+
+        let ptr = MiniBox::into_ptr(MiniBox::new([1_u64; 4]));
+        let a = unsafe { MiniBox::from_ptr(ptr) };
+        let b = mem::ManuallyDrop::new(unsafe { MiniBox::from_ptr(ptr) });
+        assert!(MiniBox::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn get() {
+        #[derive(Debug, PartialEq)]
+        struct Slot(u32);
+
+        impl Slot {
+            fn get(&self) -> u32 {
+                self.0 + 100
+            }
+        }
+
+        let mut bx = MiniBox::new(Slot(1));
+        assert_eq!(bx.get(), 101);
+        assert_eq!(MiniBox::get(&bx), &Slot(1));
+
+        MiniBox::get_mut(&mut bx).0 = 5;
+        assert_eq!(bx.get(), 105);
+        assert_eq!(*MiniBox::get(&bx), Slot(5));
+    }
+
+    #[test]
+    fn as_deref() {
+        use std::string::String;
+        use std::vec::Vec;
+
+        let bx = MiniBox::new(String::from("hello"));
+        let s: &str = MiniBox::as_deref(&bx);
+        assert_eq!(s, "hello");
+        assert_eq!(bx.as_str(), "hello");
+
+        let mut bx = MiniBox::new(Vec::from([1_u8, 2, 3]));
+        let slice: &mut [u8] = MiniBox::as_deref_mut(&mut bx);
+        slice[0] = 10;
+        let slice: &[u8] = MiniBox::as_deref(&bx);
+        assert_eq!(slice, [10, 2, 3]);
+
+        let bx = MiniBox::new(Some(String::from("world")));
+        assert_eq!(bx.as_deref(), Some("world"));
+    }
+
+    #[test]
+    fn realloc_as() {
+        // grow in place
+        let mut bx = MiniBox::<[u8; 64]>::from_fn(|i| i as u8);
+        bx[63] = 0xff;
+        let mut bx = MiniBox::realloc_as::<[u8; 128]>(bx);
+        unsafe {
+            let ptr = bx.as_mut_ptr().cast::<u8>();
+            ptr.add(64).write_bytes(0xaa, 64);
+        }
+        let bx = unsafe { bx.assume_init() };
+        assert!((0..63).all(|i| bx[i] == i as u8));
+        assert_eq!(bx[63], 0xff);
+        assert!(bx[64..].iter().all(|&x| x == 0xaa));
+
+        // shrink in place
+        let bx = MiniBox::new([7_u64; 8]);
+        let bx = unsafe { MiniBox::realloc_as::<[u64; 4]>(bx).assume_init() };
+        assert_eq!(*bx, [7; 4]);
+
+        // inline to boxed
+        let bx = MiniBox::new(0x1234_u32);
+        let bx = MiniBox::realloc_as::<[u32; 4]>(bx);
+        assert_eq!(unsafe { bx.as_ptr().cast::<u32>().read() }, 0x1234);
+
+        // boxed to inline
+        let bx = MiniBox::new([3_u8; 16]);
+        let bx = unsafe { MiniBox::realloc_as::<[u8; 4]>(bx).assume_init() };
+        assert_eq!(*bx, [3; 4]);
+
+        // different alignments
+        let bx = MiniBox::new([1_u8; 32]);
+        let bx = unsafe { MiniBox::realloc_as::<[u16; 8]>(bx).assume_init() };
+        assert_eq!(*bx, [0x0101; 8]);
+    }
+
+    #[test]
+    fn cow() {
+        use std::borrow::Cow;
+        use std::string::String;
+
+        let bx = MiniBox::new(String::from("boxed"));
+        match MiniBox::borrow_cow(&bx) {
+            Cow::Borrowed(s) => assert!(core::ptr::eq(s, &*bx)),
+            Cow::Owned(_) => panic!("expected a borrowed value"),
+        }
+
+        let cow = Cow::from(bx);
+        assert!(matches!(cow, Cow::Owned(ref s) if s == "boxed"));
+        let bx = MiniBox::new(cow.into_owned());
+        assert_eq!(*bx, "boxed");
+
+        let bx = MiniBox::new(10_u32);
+        assert_eq!(MiniBox::borrow_cow(&bx), Cow::Borrowed(&10));
+
+        let cow: Cow<'_, u32> = bx.into();
+        assert_eq!(cow, Cow::<u32>::Owned(10));
+        let bx = MiniBox::new(cow.into_owned());
+        assert_eq!(*bx, 10);
+    }
+
+    #[test]
+    fn debug_storage() {
+        use std::format;
+
+        let bx = MiniBox::new(10_u8);
+        assert_eq!(format!("{:?}", bx), "10");
+        assert_eq!(
+            format!("{:?}", MiniBox::debug_storage(&bx)),
+            "MiniBox { size_class: Inline, value: 10 }"
+        );
+
+        let bx = MiniBox::new([1_u64; 2]);
+        assert_eq!(
+            format!("{:?}", MiniBox::debug_storage(&bx)),
+            "MiniBox { size_class: Boxed, value: [1, 1] }"
+        );
+        assert!(format!("{:#?}", MiniBox::debug_storage(&MiniBox::new(()))).contains("Zero"));
+    }
+
+    #[repr(C, packed)]
+    #[derive(Clone, Copy)]
+    struct PackedSmall {
+        a: u8,
+        b: u16,
+        c: u8,
+    }
+
+    #[repr(C, packed)]
+    #[derive(Clone, Copy)]
+    struct PackedLarge {
+        a: u8,
+        b: u64,
+        c: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PackedZeroSized;
+
+    #[test]
+    fn packed_inline() {
+        assert_eq!(mem::align_of::<PackedSmall>(), 1);
+        assert_eq!(MiniBox::<PackedSmall>::SIZE_CLASS, SizeClass::Inline);
+
+        let mut bx = MiniBox::new(PackedSmall {
+            a: 1,
+            b: 0x0203,
+            c: 4,
+        });
+
+        let ptr = &*bx as *const PackedSmall;
+        assert_eq!(ptr as usize % mem::align_of::<*const ()>(), 0);
+
+        // packed fields must be copied out instead of borrowed
+        assert_eq!({ bx.a }, 1);
+        assert_eq!({ bx.b }, 0x0203);
+        assert_eq!({ bx.c }, 4);
+
+        bx.b = 0x0506;
+        let value = MiniBox::into_inner(bx.clone());
+        assert_eq!({ value.b }, 0x0506);
+        assert_eq!({ MiniBox::into_inner(bx).c }, 4);
+    }
+
+    #[test]
+    fn packed_boxed() {
+        assert_eq!(mem::align_of::<PackedLarge>(), 1);
+        assert_eq!(MiniBox::<PackedLarge>::SIZE_CLASS, SizeClass::Boxed);
+
+        let mut bx = MiniBox::new(PackedLarge { a: 1, b: 2, c: 3 });
+        assert_eq!({ bx.a }, 1);
+        assert_eq!({ bx.b }, 2);
+
+        bx.c = 30;
+        let value = MiniBox::into_inner(bx);
+        assert_eq!({ value.c }, 30);
+    }
+
+    #[test]
+    fn packed_zero_sized() {
+        assert_eq!(MiniBox::<PackedZeroSized>::SIZE_CLASS, SizeClass::Zero);
+
+        let bx = MiniBox::new(PackedZeroSized);
+        let ptr = &*bx as *const PackedZeroSized;
+        assert!(!ptr.is_null());
+        let PackedZeroSized = MiniBox::into_inner(bx);
+    }
+
+    #[test]
+    fn float_ordering() {
+        use core::cmp::Ordering;
+        use std::vec::Vec;
+
+        let nan = MiniBox::new(f64::NAN);
+        assert_eq!(nan.partial_cmp(&MiniBox::new(0.0)), None);
+        assert_eq!(
+            nan.partial_cmp(&MiniBox::new(0.0)),
+            f64::NAN.partial_cmp(&0.0)
+        );
+        assert_ne!(nan, nan);
+        assert!(!nan.lt(&MiniBox::new(0.0)));
+        assert!(!nan.ge(&MiniBox::new(0.0)));
+
+        assert_eq!(
+            MiniBox::<f64>::total_cmp(&nan, &MiniBox::new(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            MiniBox::<f64>::total_cmp(&MiniBox::new(-0.0), &MiniBox::new(0.0)),
+            Ordering::Less
+        );
+
+        let mut values = [3.0, f32::NAN, -1.0, f32::NEG_INFINITY, 0.5]
+            .iter()
+            .copied()
+            .map(MiniBox::new)
+            .collect::<Vec<_>>();
+        values.sort_by(MiniBox::<f32>::total_cmp);
+
+        let sorted = values.iter().map(|x| **x).collect::<Vec<f32>>();
+        assert_eq!(sorted[..4], [f32::NEG_INFINITY, -1.0, 0.5, 3.0]);
+        assert!(sorted[4].is_nan());
+    }
+
+    #[test]
+    fn zero_sized_strict_provenance() {
+        // meant to be run under Miri with `-Zmiri-strict-provenance`
+        #[repr(align(64))]
+        #[derive(Debug, PartialEq)]
+        struct OverAligned;
+
+        let mut bx = MiniBox::new(OverAligned);
+        assert_eq!(MiniBox::<OverAligned>::SIZE_CLASS, SizeClass::Zero);
+        assert_eq!(*bx, OverAligned);
+        assert_eq!(&*bx as *const OverAligned as usize % 64, 0);
+        let value: &mut OverAligned = &mut bx;
+        *value = OverAligned;
+        assert_eq!(MiniBox::into_inner(bx), OverAligned);
+
+        let mut bx = MiniBox::new([(); 4]);
+        bx[1] = ();
+        assert_eq!(bx.len(), 4);
+
+        let mut ptr = MiniBox::into_ptr(MiniBox::new(()));
+        unsafe {
+            *ptr.as_mut() = ();
+            assert_eq!(*ptr.as_ref(), ());
+            drop(MiniBox::from_ptr(ptr));
+        }
+
+        assert_eq!(*MiniBox::UNIT, ());
+    }
+
+    #[test]
+    fn mini_ptr_dangling() {
+        #[repr(align(32))]
+        #[derive(Debug, PartialEq)]
+        struct OverAligned;
+
+        let mut ptr = MiniPtr::<OverAligned>::dangling();
+        unsafe {
+            assert_eq!(*ptr.as_ref(), OverAligned);
+            *ptr.as_mut() = OverAligned;
+            assert_eq!(ptr.as_ref() as *const OverAligned as usize % 32, 0);
+            assert_eq!(MiniBox::into_inner(ptr.into_box()), OverAligned);
+        }
+
+        // only the address is usable for sized types
+        let ptr = MiniPtr::<[u64; 4]>::dangling();
+        let raw = unsafe { ptr.to_raw() };
+        assert!(!raw.is_null());
+        assert_eq!(raw as usize % core::mem::align_of::<[u64; 4]>(), 0);
+        assert_eq!(raw, core::ptr::NonNull::dangling().as_ptr());
+    }
+
+    #[test]
+    fn mini_option() {
+        use crate::MiniOption;
+        use core::mem::size_of;
+        use std::string::String;
+
+        assert_eq!(size_of::<MiniOption<()>>(), size_of::<usize>());
+        assert_eq!(size_of::<MiniOption<u8>>(), size_of::<usize>());
+        assert_eq!(size_of::<MiniOption<[usize; 2]>>(), size_of::<usize>());
+        assert_eq!(size_of::<MiniOption<String>>(), size_of::<usize>());
+
+        fn check<T: Clone + PartialEq + core::fmt::Debug>(value: T) {
+            let mut opt = MiniOption::<T>::NONE;
+            assert!(opt.is_none());
+            assert_eq!(opt.as_ref(), None);
+            assert_eq!(opt.as_mut(), None);
+            assert_eq!(opt.take(), None);
+
+            assert_eq!(opt.replace(value.clone()), None);
+            assert!(opt.is_some());
+            assert_eq!(opt.as_ref(), Some(&value));
+            assert_eq!(opt.clone(), opt);
+            assert_ne!(opt, MiniOption::none());
+
+            assert_eq!(opt.take(), Some(value.clone()));
+            assert!(opt.is_none());
+
+            let opt = MiniOption::from(Some(value.clone()));
+            assert_eq!(Option::from(opt), Some(value));
+        }
+
+        check(());
+        check(true);
+        check(0_u8);
+        check([1_u16, 0, 3]);
+        check([0_u64, 7]);
+        check([usize::MAX; 2]);
+        check(String::from("hello"));
+        check(Some(MiniBox::new(0_u32)));
+
+        let mut opt = MiniOption::some([0_u8; 3]);
+        opt.as_mut().unwrap()[1] = 5;
+        assert_eq!(opt.as_ref(), Some(&[0, 5, 0]));
+        assert_eq!(std::format!("{:?}", opt), "Some([0, 5, 0])");
+        assert_eq!(std::format!("{:?}", MiniOption::<u8>::default()), "None");
+    }
+
+    #[test]
+    fn inline_provenance_round_trip() {
+        // meant to be run under Miri (with and without `-Zmiri-tree-borrows`)
+        let mut bx = MiniBox::new([1_u16, 2, 3, 4]);
+        assert_eq!(MiniBox::<[u16; 4]>::SIZE_CLASS, SizeClass::Inline);
+
+        for i in 0..4 {
+            let mut ptr = MiniBox::into_ptr(bx);
+            unsafe {
+                assert_eq!(ptr.as_ref()[i], i as u16 + 1);
+                ptr.as_mut()[i] *= 10;
+                let first = &mut ptr.as_mut()[0];
+                *first += 1;
+                assert_eq!(ptr.as_ref()[i], (i as u16 + 1) * 10 + (i == 0) as u16);
+            }
+
+            bx = unsafe { MiniBox::from_ptr(ptr) };
+            let value = &mut *bx;
+            value[i] -= 1;
+            assert_eq!(bx[i], (i as u16 + 1) * 10 - 1 + (i == 0) as u16);
+            let shared = &*bx;
+            let shared_again = &*bx;
+            assert_eq!(shared, shared_again);
+        }
+
+        assert_eq!(*bx, [13, 19, 29, 39]);
+    }
+
+    #[test]
+    fn stable_call() {
+        use crate::{MiniFn, MiniFnMut, MiniFnOnce};
+        use std::string::String;
+
+        fn size_class_of<T>(_: &MiniBox<T>) -> SizeClass {
+            MiniBox::<T>::SIZE_CLASS
+        }
+
+        let zero = MiniBox::new(|| 42_u32);
+        assert_eq!(size_class_of(&zero), SizeClass::Zero);
+        assert_eq!(zero.call(()), 42);
+
+        let prefix = String::from("hello ");
+        let greet = MiniBox::new(move |name: &str| prefix.clone() + name);
+        assert_eq!(size_class_of(&greet), SizeClass::Boxed);
+        assert_eq!(MiniFn::call(&greet, ("world",)), "hello world");
+
+        let big = [1_u64; 8];
+        let sum = MiniBox::new(move |a: u64, b: u64, c: u64| big.iter().sum::<u64>() + a + b + c);
+        assert_eq!(size_class_of(&sum), SizeClass::Boxed);
+        assert_eq!(sum.call((1, 2, 3)), 14);
+
+        let mut total = 0;
+        let mut add = MiniBox::new(|x: i32| total += x);
+        assert_eq!(size_class_of(&add), SizeClass::Inline);
+        add.call_mut((5,));
+        add.call_mut((6,));
+        drop(add);
+        assert_eq!(total, 11);
+
+        let name = String::from("once");
+        let consume = MiniBox::new(move || name);
+        assert_eq!(MiniFnOnce::call_once(consume, ()), "once");
+    }
+
+    #[test]
+    fn write_default() {
+        use std::string::String;
+        use std::vec::Vec;
+
+        let bx = MiniBox::<u32>::new_uninit().write_default();
+        assert_eq!(*bx, 0);
+
+        let bx = MiniBox::<(String, Vec<u8>)>::new_uninit().write_default();
+        assert_eq!(*bx, (String::new(), Vec::new()));
+
+        let bx = MiniBox::<[u64; 32]>::new_zeroed().write_default();
+        assert_eq!(*bx, [0; 32]);
+
+        let bx = MiniBox::<()>::new_uninit().write_default();
+        assert_eq!(bx, MiniBox::UNIT);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn as_fd() {
+        use std::fs::File;
+        use std::os::unix::io::{AsFd, AsRawFd};
+
+        let file = File::open(file!()).unwrap();
+        let fd = file.as_raw_fd();
+
+        let bx = MiniBox::new(file);
+        assert_eq!(MiniBox::<File>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(bx.as_raw_fd(), fd);
+        assert_eq!(bx.as_fd().as_raw_fd(), fd);
+
+        fn takes_fd<F: AsRawFd>(value: &F) -> i32 {
+            value.as_raw_fd()
+        }
+
+        assert_eq!(takes_fd(&bx), fd);
+    }
+
+    #[test]
+    fn as_ref_path() {
+        use std::fs::{self, File};
+        use std::io::Read;
+        use std::path::{Path, PathBuf};
+        use std::string::String;
+
+        let path = std::env::temp_dir().join(format!("minibox-as-ref-path-{}", std::process::id()));
+        fs::write(&path, "hello from a MiniBox<PathBuf>").unwrap();
+
+        let bx = MiniBox::new(path);
+        assert_eq!(MiniBox::<PathBuf>::SIZE_CLASS, SizeClass::Boxed);
+        assert_eq!(AsRef::<Path>::as_ref(&bx), &**bx);
+
+        let mut contents = String::new();
+        File::open(&bx)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "hello from a MiniBox<PathBuf>");
+
+        fs::remove_file(&bx).unwrap();
+        assert!(!bx.exists());
+    }
+
+    #[test]
+    fn word_round_trip() {
+        let bx = MiniBox::new([1_u8, 2, 3, 4]);
+        let word = unsafe { MiniBox::as_word(&bx) };
+
+        #[cfg(target_endian = "little")]
+        assert_eq!(word, 0x04030201);
+        #[cfg(target_endian = "big")]
+        assert_eq!(word, 0x01020304 << (8 * (mem::size_of::<usize>() - 4)));
+
+        let bx = unsafe { MiniBox::<[u8; 4]>::from_word(word) };
+        assert_eq!(*bx, [1, 2, 3, 4]);
+
+        let bx = MiniBox::new(usize::MAX - 1);
+        assert_eq!(unsafe { MiniBox::as_word(&bx) }, usize::MAX - 1);
+        assert_eq!(unsafe { MiniBox::as_word(&MiniBox::new(())) }, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn word_boxed() {
+        unsafe { MiniBox::as_word(&MiniBox::new([0_usize; 2])) };
+    }
+
+    #[test]
+    fn new_matches_write() {
+        fn check<T: PartialEq + core::fmt::Debug + Clone>(value: T) {
+            let a = MiniBox::new(value.clone());
+            let b = MiniBox::new_uninit().write(value);
+            assert_eq!(a, b);
+        }
+
+        check(7_u64);
+        check(7_u8);
+        check((1_u8, 2_u16));
+        check(());
+        check([1_u32; 8]);
+        check(std::string::String::from("hello"));
+        check(Some(&10));
+
+        // the bytes after an inline value are zeroed, just like with `new_uninit`
+        let bx = MiniBox::new(1_u8);
+        let raw = unsafe { MiniBox::copy_storage_word(&bx).to_raw() };
+        assert_eq!(raw as usize, unsafe { MiniBox::as_word(&bx) });
+    }
+
+    #[test]
+    fn clone_deep() {
+        use std::vec::Vec;
+
+        let bx = MiniBox::new(Vec::from([1_u8, 2, 3]));
+        let ptr = MiniBox::into_ptr(bx);
+        let shallow = ptr;
+
+        let clone = unsafe { ptr.clone_deep() };
+        assert_eq!(*clone, [1, 2, 3]);
+        assert_ne!(clone.as_ptr(), unsafe { ptr.as_ref() }.as_ptr());
+        assert_eq!(
+            unsafe { shallow.as_ref() }.as_ptr(),
+            unsafe { ptr.as_ref() }.as_ptr()
+        );
+
+        let ptr = MiniBox::into_ptr(MiniBox::new([7_u64; 4]));
+        let clone = unsafe { ptr.clone_deep() };
+        assert_eq!(*clone, [7; 4]);
+        assert_ne!(&*clone as *const [u64; 4], unsafe { ptr.as_ref() }
+            as *const [u64; 4]);
+
+        drop(unsafe { ptr.into_box() });
+        assert_eq!(*clone, [7; 4]);
+        drop(unsafe { shallow.into_box() });
+    }
+
+    #[test]
+    fn size_class_u8() {
+        use core::convert::TryFrom;
+
+        for &size_class in &[SizeClass::Zero, SizeClass::Inline, SizeClass::Boxed] {
+            let byte = u8::from(size_class);
+            assert_eq!(size_class, byte);
+            assert_eq!(SizeClass::try_from(byte), Ok(size_class));
+        }
+
+        assert_eq!(u8::from(SizeClass::Boxed), 2);
+        assert_eq!(SizeClass::try_from(3), Err(InvalidSizeClass));
+        assert_eq!(SizeClass::try_from(u8::MAX), Err(InvalidSizeClass));
+        assert_ne!(SizeClass::Zero, 1);
+    }
+
+    #[test]
+    fn debug_mini_ptr() {
+        use std::format;
+
+        let ptr = MiniBox::into_ptr(MiniBox::new(()));
+        assert_eq!(format!("{:?}", ptr), "MiniPtr::<()>(Zero)");
+
+        let ptr = MiniBox::into_ptr(MiniBox::new(10_u16));
+        assert_eq!(format!("{:?}", ptr), "MiniPtr::<u16>(Inline)");
+        drop(unsafe { ptr.into_box() });
+
+        let ptr = MiniBox::into_ptr(MiniBox::new([1_u64; 2]));
+        assert_eq!(format!("{:?}", ptr), "MiniPtr::<[u64; 2]>(Boxed)");
+        assert_eq!(
+            format!("{:?}", unsafe { ptr.debug_word() }),
+            format!("MiniPtr::<[u64; 2]>(Boxed, {:#x})", unsafe { ptr.to_raw() }
+                as usize)
+        );
+        drop(unsafe { ptr.into_box() });
+
+        let ptr = MiniBox::into_ptr(MiniBox::new(usize::MAX));
+        assert_eq!(
+            format!("{:?}", unsafe { ptr.debug_word() }),
+            format!("MiniPtr::<usize>(Inline, {:#x})", usize::MAX)
+        );
+    }
+
+    #[test]
+    fn debug_pointer() {
+        use std::format;
+
+        let bx = MiniBox::new([7_u8; 4096]);
+        let debug = format!("{:?}", MiniBox::debug_pointer(&bx));
+        assert_eq!(debug, format!("MiniBox(Boxed @ {:p})", &*bx));
+        assert!(debug.contains("0x"));
+        assert!(debug.len() < 64);
+
+        let bx = MiniBox::new(10_u8);
+        assert_eq!(
+            format!("{:?}", MiniBox::debug_pointer(&bx)),
+            format!("MiniBox(Inline @ {:p})", &*bx)
+        );
+    }
+
+    #[test]
+    fn zeroed_composite() {
+        use core::num::NonZeroU8;
+
+        let bx = MiniBox::<([u8; 4], u32, Option<NonZeroU8>)>::zeroed();
+        assert_eq!(
+            MiniBox::<([u8; 4], u32, Option<NonZeroU8>)>::SIZE_CLASS,
+            SizeClass::Boxed
+        );
+        assert_eq!(*bx, ([0; 4], 0, None));
+
+        let bx = MiniBox::<[(u8, Option<NonZeroU8>); 4]>::zeroed();
+        assert_eq!(*bx, [(0, None); 4]);
+
+        let bx = MiniBox::<([[u16; 2]; 2], (i8, Option<&u8>))>::zeroed();
+        assert_eq!(*bx, ([[0; 2]; 2], (0, None)));
+    }
+
+    #[test]
+    fn zeroed_inline_padding() {
+        fn storage_word<T>(bx: &MiniBox<T>) -> usize {
+            // `zeroed` writes a null pointer to inline storage, so every byte of the word
+            // (including the bytes past the end of `T`, and any padding) is initialized
+            unsafe { MiniBox::copy_storage_word(bx).to_raw() as usize }
+        }
+
+        let bx = MiniBox::<(u8,)>::zeroed();
+        assert_eq!(MiniBox::<(u8,)>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(*bx, (0,));
+        assert_eq!(storage_word(&bx), 0);
+
+        let bx = MiniBox::<(u8, u8, u8)>::zeroed();
+        assert_eq!(*bx, (0, 0, 0));
+        assert_eq!(storage_word(&bx), 0);
+
+        // `(u8, u16)` has a padding byte
+        let bx = MiniBox::<(u8, u16)>::zeroed();
+        assert_eq!(mem::size_of::<(u8, u16)>(), 4);
+        assert_eq!(*bx, (0, 0));
+        assert_eq!(storage_word(&bx), 0);
+
+        let bx = MiniBox::<[u8; 0]>::zeroed();
+        assert_eq!(*bx, []);
+    }
+
+    #[test]
+    fn unit() {
+        const UNIT: MiniBox<()> = MiniBox::UNIT;
+        static STATIC_UNIT: MiniBox<()> = MiniBox::UNIT;
+        const UNITS: [MiniBox<()>; 3] = [MiniBox::UNIT; 3];
+
+        assert_eq!(UNIT, STATIC_UNIT);
+        assert_eq!(UNITS.len(), 3);
+        assert_eq!(MiniBox::from(()), UNIT);
+        assert_eq!(MiniBox::<()>::default(), UNIT);
+        assert_eq!(MiniBox::new_zst(()), UNIT);
+        MiniBox::into_inner(UNIT);
+    }
+
+    #[test]
+    fn phantom_data() {
+        use core::marker::PhantomData;
+
+        const PHANTOM: MiniBox<PhantomData<str>> = MiniBox::new_zst(PhantomData);
+
+        assert_eq!(MiniBox::<PhantomData<str>>::SIZE_CLASS, SizeClass::Zero);
+        assert_eq!(MiniBox::from(PhantomData), PHANTOM);
+        assert_eq!(MiniBox::<PhantomData<str>>::default(), PHANTOM);
+        assert_eq!(MiniBox::new(PhantomData::<str>), PHANTOM);
+    }
+
+    #[test]
+    fn clone_to() {
+        use std::string::String;
+
+        let bx = MiniBox::new(String::from("hello"));
+        let mut buffer = MaybeUninit::<[String; 2]>::uninit();
+        let dst = buffer.as_mut_ptr().cast::<String>();
+
+        unsafe {
+            MiniBox::clone_to(&bx, dst);
+            MiniBox::clone_to(&bx, dst.add(1));
+        }
+
+        let buffer = unsafe { buffer.assume_init() };
+        assert_eq!(buffer, ["hello", "hello"]);
+
+        let bx = MiniBox::new(7_u16);
+        let mut dst = 0;
+        unsafe { MiniBox::clone_to(&bx, &mut dst) };
+        assert_eq!(dst, 7);
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn clone_to_uninit() {
+        use core::clone::CloneToUninit;
+        use std::string::String;
+
+        let bx = MiniBox::new(String::from("hello"));
+
+        // clone the value into a `MiniBox<MaybeUninit<String>>`
+        let mut storage = MiniBox::<String>::new_uninit();
+        unsafe { (*bx).clone_to_uninit(storage.as_mut_ptr().cast()) };
+        let clone = unsafe { storage.assume_init() };
+        assert_eq!(clone, bx);
+
+        // clone the whole `MiniBox`
+        let mut storage = MiniBox::<MiniBox<String>>::new_uninit();
+        unsafe { bx.clone_to_uninit(storage.as_mut_ptr().cast()) };
+        let clone = unsafe { storage.assume_init() };
+        assert_eq!(**clone, "hello");
+    }
+
+    #[test]
+    fn into_vec() {
+        let vec = MiniBox::into_vec(MiniBox::new([1_u8, 2, 3, 4]));
+        assert_eq!(MiniBox::<[u8; 4]>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(vec, [1, 2, 3, 4]);
+        assert_eq!(vec.capacity(), 4);
+
+        let bx = MiniBox::<[u64; 512]>::from_fn(|i| i as u64);
+        let addr = bx.as_ptr();
+        let vec = MiniBox::into_vec(bx);
+        assert_eq!(vec.as_ptr(), addr);
+        assert_eq!(vec.len(), 512);
+        assert_eq!(vec.capacity(), 512);
+        assert!(vec.iter().enumerate().all(|(i, &x)| x == i as u64));
+
+        let vec = MiniBox::into_vec(MiniBox::<[std::string::String; 0]>::new([]));
+        assert!(vec.is_empty());
+
+        let mut vec = MiniBox::into_vec(MiniBox::new([(); 3]));
+        vec.push(());
+        assert_eq!(vec.len(), 4);
+    }
+
+    #[test]
+    fn leak_as_slice() {
+        assert_eq!(MiniBox::<[u32; 4]>::SIZE_CLASS, SizeClass::Boxed);
+        let bx = MiniBox::new([1, 2, 3, 4]);
+        let address = bx.as_ptr();
+        let slice: &'static mut [u32] = MiniBox::leak_as_slice(bx);
+        // boxed arrays keep their allocation
+        assert_eq!(slice.as_ptr(), address);
+        slice[1] = 20;
+        slice.reverse();
+        assert_eq!(slice, [4, 3, 20, 1]);
+
+        // free the allocation, so that Miri's leak check still catches real leaks
+        drop(unsafe { Box::from_raw(slice) });
+
+        // inline arrays are moved to the heap
+        let slice: &'static mut [u16] = MiniBox::leak_as_slice(MiniBox::new([1, 2]));
+        assert_eq!(MiniBox::<[u16; 2]>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(slice, [1, 2]);
+        slice[0] += 10;
+        assert_eq!(slice, [11, 2]);
+        drop(unsafe { Box::from_raw(slice) });
+
+        let slice: &'static mut [u8] = MiniBox::leak_as_slice(MiniBox::new([]));
+        assert!(slice.is_empty());
+        drop(unsafe { Box::from_raw(slice) });
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let bx = MiniBox::<[u16; 3]>::try_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(*bx, [1, 2, 3]);
+
+        let data = (0..8192).map(|i| i as u8).collect::<std::vec::Vec<_>>();
+        let bx = MiniBox::<[u8; 8192]>::try_from_slice(&data).unwrap();
+        assert_eq!(bx[..], data[..]);
+
+        assert!(MiniBox::<[u8; 8192]>::try_from_slice(&data[1..]).is_none());
+        assert!(MiniBox::<[u16; 2]>::try_from_slice(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn from_copy_slice() {
+        // meant to be run under Miri, which reports any uninitialized bytes left in the array
+        let bx = MiniBox::<[u8; 4]>::from_copy_slice(&[1, 2, 3, 4]);
+        assert_eq!(*bx, [1, 2, 3, 4]);
+
+        let data = (0..64).collect::<std::vec::Vec<u64>>();
+        let bx = MiniBox::<[u64; 64]>::from_copy_slice(&data);
+        assert_eq!(bx[..], data[..]);
+        assert_eq!(bx.iter().sum::<u64>(), 63 * 64 / 2);
+
+        let bx = MiniBox::<[(); 2]>::from_copy_slice(&[(), ()]);
+        assert_eq!(bx.len(), 2);
+
+        let bx = MiniBox::<[u8; 0]>::from_copy_slice(&[]);
+        assert!(bx.is_empty());
+    }
+
+    #[test]
+    #[should_panic = "source slice length (3) does not match destination array length (4)"]
+    fn from_copy_slice_short() {
+        MiniBox::<[u8; 4]>::from_copy_slice(&[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic = "source slice length (5) does not match destination array length (4)"]
+    fn from_copy_slice_long() {
+        MiniBox::<[u64; 4]>::from_copy_slice(&[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn try_from_slice_small_stack() {
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let data = std::vec![0xab_u8; 1 << 20];
+                let bx = MiniBox::<[u8; 1 << 20]>::try_from_slice(&data).unwrap();
+                assert!(bx.iter().all(|&x| x == 0xab));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn cmp_by() {
+        let mut values = std::vec![
+            MiniBox::new((3_u8, "c")),
+            MiniBox::new((1, "b")),
+            MiniBox::new((2, "a")),
+        ];
+
+        values.sort_by(|a, b| MiniBox::cmp_by(a, b, |a, b| a.1.cmp(b.1)));
+        let keys = values.iter().map(|bx| bx.0).collect::<std::vec::Vec<_>>();
+        assert_eq!(keys, [2, 1, 3]);
+
+        values.sort_by(|a, b| MiniBox::cmp_by(a, b, |a, b| b.0.cmp(&a.0)));
+        let keys = values.iter().map(|bx| bx.1).collect::<std::vec::Vec<_>>();
+        assert_eq!(keys, ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn dangling_uninit() {
+        const SLOT: MiniBox<MaybeUninit<u32>> = MiniBox::<u32>::dangling_uninit();
+        static STATIC_SLOT: MiniBox<MaybeUninit<u16>> = MiniBox::<u16>::dangling_uninit();
+        const ZST_SLOT: MiniBox<MaybeUninit<()>> = MiniBox::<()>::dangling_uninit();
+
+        assert_eq!(*SLOT.write(10), 10);
+        assert_eq!(*SLOT.write(20), 20);
+        assert_eq!(*ZST_SLOT.write(()), ());
+
+        let slot: &MiniBox<MaybeUninit<u16>> = &STATIC_SLOT;
+        assert_eq!(slot.as_ptr() as usize % mem::align_of::<u16>(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(not(miri))]
+    fn dangling_uninit_boxed() {
+        MiniBox::<[u32; 4]>::dangling_uninit();
+    }
+
+    #[test]
+    fn into_boxed_slice() {
+        let bx = MiniBox::new(std::vec![1_u32, 2, 3, 4]);
+        let buffer = bx.as_ptr();
+        let bx = MiniBox::into_boxed_slice(bx);
+        assert_eq!(bx.len(), 4);
+        assert_eq!(bx.as_ptr(), buffer);
+        assert_eq!(**bx, [1, 2, 3, 4]);
+
+        let mut vec = std::vec::Vec::with_capacity(32);
+        vec.extend_from_slice(&[5_u8, 6]);
+        let bx = MiniBox::into_boxed_slice(MiniBox::new(vec));
+        assert_eq!(**bx, [5, 6]);
+    }
+
+    #[test]
+    fn iterator_forwarding() {
+        use std::vec::Vec;
+
+        let values = (1..=10).collect::<Vec<u64>>();
+
+        let bx = MiniBox::new(values.clone().into_iter());
+        assert_eq!(
+            MiniBox::<std::vec::IntoIter<u64>>::SIZE_CLASS,
+            SizeClass::Boxed
+        );
+        assert_eq!(bx.fold(1, |acc, x| acc * 2 + x), 3060);
+
+        assert_eq!(MiniBox::new(values.iter()).count(), 10);
+        assert_eq!(MiniBox::new(values.iter()).sum::<u64>(), 55);
+        assert_eq!(MiniBox::new(values.iter()).product::<u64>(), 3628800);
+        assert_eq!(
+            MiniBox::new(values.iter().map(|x| x * 2)).collect::<Vec<_>>(),
+            values.iter().map(|x| x * 2).collect::<Vec<_>>()
+        );
+
+        let mut sum = 0;
+        MiniBox::new(1..=4).for_each(|x| sum += x);
+        assert_eq!(sum, 10);
+
+        let mut bx = MiniBox::new(values.into_iter());
+        assert_eq!(bx.next(), Some(1));
+        assert_eq!(
+            bx.fold(Vec::new(), |mut acc, x| {
+                acc.push(x);
+                acc
+            }),
+            (2..=10).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn double_ended_iterator_forwarding() {
+        use std::vec::Vec;
+
+        let values = (1..=10).collect::<Vec<u64>>();
+
+        let bx = MiniBox::new(values.clone().into_iter());
+        assert_eq!(
+            bx.rfold(1, |acc, x| acc * 2 + x),
+            values.iter().rfold(1, |acc, x| acc * 2 + x)
+        );
+
+        let mut bx = MiniBox::new(values.iter());
+        assert_eq!(bx.next_back(), Some(&10));
+        assert_eq!(
+            bx.rfold(Vec::new(), |mut acc, &x| {
+                acc.push(x);
+                acc
+            }),
+            (1..=9).rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn try_rfold_forwarding() {
+        use std::vec::Vec;
+
+        let values = (1..=10).collect::<Vec<u64>>();
+
+        // `try_rfold` stops at the first error, and leaves the rest of the items in the iterator
+        let mut bx = MiniBox::new(values.into_iter());
+        let result = bx.try_rfold(0, |acc, x| if x > 7 { Ok(acc + x) } else { Err(x) });
+        assert_eq!(result, Err(7));
+        assert_eq!(bx.next_back(), Some(6));
+
+        let mut bx = MiniBox::new(1..=4_u32);
+        assert_eq!(bx.try_rfold(0_u32, |acc, x| acc.checked_add(x)), Some(10));
+        assert_eq!(bx.next(), None);
+    }
+
+    #[test]
+    fn new_overaligned() {
+        let bx = MiniBox::new_overaligned::<64>([1.0_f32, 2.0, 3.0, 4.0]);
+        assert_eq!(
+            MiniBox::<Aligned<[f32; 4], 64>>::SIZE_CLASS,
+            SizeClass::Boxed
+        );
+        assert_eq!(&**bx as *const [f32; 4] as usize % 64, 0);
+        assert_eq!(**bx, [1.0, 2.0, 3.0, 4.0]);
+
+        let mut bx = MiniBox::new_overaligned::<4096>(0_u8);
+        assert_eq!(&**bx as *const u8 as usize % 4096, 0);
+        **bx = 10;
+        assert_eq!(MiniBox::into_inner(bx).into_inner(), 10);
+
+        // alignments that fit in a pointer are stored inline
+        let bx = MiniBox::new_overaligned::<4>(1_u8);
+        assert_eq!(MiniBox::<Aligned<u8, 4>>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(&**bx as *const u8 as usize % 4, 0);
+    }
+
+    #[test]
+    fn uninhabited() {
+        use core::convert::Infallible;
+
+        assert_eq!(MiniBox::<Infallible>::SIZE_CLASS, SizeClass::Zero);
+        assert_eq!(
+            mem::size_of::<MiniBox<Infallible>>(),
+            mem::size_of::<usize>()
+        );
+
+        fn never_fails(value: u8) -> Result<MiniBox<u8>, MiniBox<Infallible>> {
+            Ok(MiniBox::new(value))
+        }
+
+        fn unwrap<T>(result: Result<T, MiniBox<Infallible>>) -> T {
+            match result {
+                Ok(value) => value,
+                Err(never) => match *never {},
+            }
+        }
+
+        assert_eq!(*unwrap(never_fails(3)), 3);
+
+        // staging storage for uninhabited types is fine, as long as it's never initialized
+        let uninit = MiniBox::<Infallible>::new_uninit();
+        drop(uninit);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn error_source_chain() {
+        use core::fmt;
+        use std::error::Error;
+        use std::string::{String, ToString};
+        use std::vec::Vec;
+
+        #[derive(Debug)]
+        struct Inner(u32);
+
+        #[derive(Debug)]
+        struct Middle {
+            code: u64,
+            inner: Inner,
+        }
+
+        #[derive(Debug)]
+        struct Outer {
+            middle: MiniBox<Middle>,
+        }
+
+        impl fmt::Display for Inner {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "inner {}", self.0)
+            }
+        }
+
+        impl fmt::Display for Middle {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "middle {}", self.code)
+            }
+        }
+
+        impl fmt::Display for Outer {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("outer")
+            }
+        }
+
+        impl Error for Inner {}
+
+        impl Error for Middle {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.inner)
+            }
+        }
+
+        impl Error for Outer {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.middle)
+            }
+        }
+
+        fn chain(mut err: &(dyn Error + 'static)) -> Vec<String> {
+            let mut chain = vec![err.to_string()];
+            while let Some(source) = err.source() {
+                chain.push(source.to_string());
+                err = source;
+            }
+            chain
+        }
+
+        fn outer() -> Outer {
+            Outer {
+                middle: MiniBox::new(Middle {
+                    code: 7,
+                    inner: Inner(3),
+                }),
+            }
+        }
+
+        assert_eq!(MiniBox::<Inner>::SIZE_CLASS, SizeClass::Inline);
+        assert_eq!(MiniBox::<Middle>::SIZE_CLASS, SizeClass::Boxed);
+
+        let expected = ["outer", "middle 7", "inner 3"];
+        assert_eq!(chain(&outer()), expected);
+        assert_eq!(chain(&MiniBox::new(outer())), expected);
+
+        let bx = MiniBox::new(MiniBox::new(outer()));
+        assert_eq!(chain(&bx), expected);
+
+        // the sources are the errors stored in the boxes, not copies
+        let source = bx.source().unwrap();
+        let middle = source.downcast_ref::<MiniBox<Middle>>().unwrap();
+        assert!(core::ptr::eq(&**middle, &*bx.middle));
+        let inner = source.source().unwrap().downcast_ref::<Inner>().unwrap();
+        assert!(core::ptr::eq(inner, &bx.middle.inner));
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn error_provide() {
+        use std::error::{request_ref, Error, Request};
+
+        #[derive(Debug, PartialEq)]
+        struct Context(&'static str);
+
+        #[derive(Debug)]
+        struct ContextError {
+            context: Context,
+        }
+
+        impl core::fmt::Display for ContextError {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("context error")
+            }
+        }
+
+        impl Error for ContextError {
+            fn provide<'a>(&'a self, request: &mut Request<'a>) {
+                request.provide_ref::<Context>(&self.context);
+            }
+        }
+
+        let bx = MiniBox::new(ContextError {
+            context: Context("while testing"),
+        });
+
+        let err: &dyn Error = &bx;
+        assert_eq!(request_ref::<Context>(err), Some(&Context("while testing")));
+    }
+
+    #[test]
+    fn map_realloc() {
+        let bx = MiniBox::<[u8; 64]>::from_fn(|i| i as u8);
+        let bx = MiniBox::map_realloc(bx, |old| {
+            let mut new = [0_u8; 128];
+            new[..64].copy_from_slice(&old);
+            new[64..].copy_from_slice(&old);
+            new
+        });
+        assert!(bx.iter().enumerate().all(|(i, &x)| x == (i % 64) as u8));
+
+        let bx = MiniBox::map_realloc(MiniBox::new(3_u8), |x| [x; 32]);
+        assert_eq!(*bx, [3; 32]);
+
+        let bx = MiniBox::map_realloc(bx, |x| x[0] as u64);
+        assert_eq!(*bx, 3);
+
+        let result = std::panic::catch_unwind(|| {
+            MiniBox::map_realloc(MiniBox::new([0_u8; 64]), |_| -> [u8; 128] { panic!() })
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn trusted_len() {
+        use core::iter::TrustedLen;
+        use std::vec::Vec;
+
+        fn assert_trusted_len<I: TrustedLen>(iter: I) -> I {
+            iter
+        }
+
+        let iter = assert_trusted_len(MiniBox::new(0..100_u32));
+        let vec = Vec::from_iter(iter);
+        assert_eq!(vec.len(), 100);
+        assert_eq!(vec.capacity(), 100);
+        assert!(vec.iter().copied().eq(0..100));
+
+        let iter = assert_trusted_len(MiniBox::new([1_u64; 8].into_iter().chain(0..4)));
+        let vec = iter.collect::<Vec<_>>();
+        assert_eq!(vec.len(), 12);
+    }
+
+    #[test]
+    fn sort() {
+        use core::cmp::Reverse;
+        use std::vec::Vec;
+
+        let values = [5, -3, 0, 12, -3, 7, i32::MIN, i32::MAX, 1];
+        let mut sorted = values;
+        sorted.sort_unstable();
+
+        let mut vec = values.iter().copied().map(MiniBox::new).collect::<Vec<_>>();
+        vec.sort();
+        assert!(vec.iter().map(|x| **x).eq(sorted.iter().copied()));
+
+        let mut vec = values.iter().copied().map(MiniBox::new).collect::<Vec<_>>();
+        vec.sort_unstable();
+        assert!(vec.iter().map(|x| **x).eq(sorted.iter().copied()));
+
+        let mut vec = values.iter().copied().map(MiniBox::new).collect::<Vec<_>>();
+        vec.sort_by_key(|x| Reverse(**x));
+        assert!(vec.iter().map(|x| **x).eq(sorted.iter().rev().copied()));
+
+        let mut vec = values
+            .iter()
+            .map(|&x| Reverse(MiniBox::new(x)))
+            .collect::<Vec<_>>();
+        vec.sort();
+        assert!(vec.iter().map(|x| *x.0).eq(sorted.iter().rev().copied()));
+
+        // boxed elements, sorting only moves the pointers
+        let mut vec = values
+            .iter()
+            .map(|&x| MiniBox::new([x as i64, 0]))
+            .collect::<Vec<_>>();
+        let addrs = vec.iter().map(|x| x.as_ptr()).collect::<Vec<_>>();
+        vec.sort_by_key(|x| x[0]);
+        assert!(vec.iter().map(|x| x[0] as i32).eq(sorted.iter().copied()));
+        assert!(vec.iter().all(|x| addrs.contains(&x.as_ptr())));
+    }
+
+    #[test]
+    fn btree_range() {
+        use std::collections::{BTreeMap, BTreeSet};
+        use std::vec::Vec;
+
+        let set = (0..10)
+            .rev()
+            .map(MiniBox::new)
+            .collect::<BTreeSet<MiniBox<i32>>>();
+
+        let range = set.range(3..6).map(|x| **x).collect::<Vec<_>>();
+        assert_eq!(range, [3, 4, 5]);
+
+        let range = set.range(..=2).map(|x| **x).collect::<Vec<_>>();
+        assert_eq!(range, [0, 1, 2]);
+
+        assert!(set.contains(&7));
+        assert!(!set.contains(&10));
+
+        let map = (0..4_u64)
+            .map(|i| (MiniBox::new([i; 4]), i))
+            .collect::<BTreeMap<MiniBox<[u64; 4]>, u64>>();
+        assert_eq!(map.get(&[2; 4]), Some(&2));
+        assert_eq!(map.range([1; 4]..).count(), 3);
+    }
+
+    #[test]
+    fn hash_matches_box() {
+        use core::hash::{Hash, Hasher};
+        use std::boxed::Box;
+        use std::collections::hash_map::DefaultHasher;
+        use std::string::String;
+
+        fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        fn check<T: Hash + Clone>(value: T) {
+            let bare = hash(&value);
+            assert_eq!(hash(&Box::new(value.clone())), bare);
+            assert_eq!(hash(&MiniBox::new(value)), bare);
+        }
+
+        check(());
+        check(7_u8);
+        check(0xdead_beef_u32);
+        check((1_u16, 'x'));
+        check([1_u64, 2, 3, 4]);
+        check(String::from("hello"));
+        check(Some(MiniBox::new(3_i64)));
+
+        // a value hashed through several layers of boxes still hashes like the value
+        let value = [9_u32; 5];
+        assert_eq!(
+            hash(&MiniBox::new(Box::new(value))),
+            hash(&Box::new(MiniBox::new(value)))
+        );
+    }
+
+    #[test]
+    fn hash_array_as_slice() {
+        use core::hash::{Hash, Hasher};
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashMap;
+
+        fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let array = [1_u8, 2, 3, 4, 5, 6, 7, 8, 9];
+        let bx = MiniBox::new(array);
+        assert_eq!(hash(&bx), hash(&array));
+        assert_eq!(hash(&bx), hash(&array[..]));
+        assert_ne!(hash(&bx), hash(&array[1..]));
+
+        let bx = MiniBox::new([1_u16, 2]);
+        assert_eq!(hash(&bx), hash(&[1_u16, 2][..]));
+
+        let mut map = HashMap::new();
+        map.insert(MiniBox::new(*b"hello world"), 1);
+        map.insert(MiniBox::new(*b"hello_world"), 2);
+        assert_eq!(map.get(&b"hello world"[..]), Some(&1));
+        assert_eq!(map.get(b"hello_world"), Some(&2));
+        assert_eq!(map.get(&b"hello"[..]), None);
+    }
+
+    #[test]
+    fn eq_shared() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let bx = MiniBox::new(5_i32);
+        assert!(bx == Rc::new(5));
+        assert!(Rc::new(5) == bx);
+        assert!(bx != Rc::new(6));
+        assert!(bx == Arc::new(5));
+        assert!(Arc::new(6) != bx);
+
+        let bx = MiniBox::new([1_u64; 4]);
+        assert!(bx == Rc::new([1; 4]));
+        assert!(Arc::new([1; 4]) == bx);
+
+        // heterogeneous comparisons forward to `T: PartialEq<U>`
+        let bx = MiniBox::new(std::string::String::from("hi"));
+        assert!(bx == Rc::new("hi"));
+        assert!(Arc::new("hi") != MiniBox::new(std::string::String::from("ho")));
+    }
+
+    #[test]
+    fn parse() {
+        use std::string::String;
+
+        let bx = "42".parse::<MiniBox<i32>>().unwrap();
+        assert_eq!(*bx, 42);
+
+        let bx: MiniBox<f64> = "-1.5".parse().unwrap();
+        assert_eq!(*bx, -1.5);
+
+        let bx: MiniBox<String> = "hello".parse().unwrap();
+        assert_eq!(*bx, "hello");
+
+        let err = "4x2".parse::<MiniBox<i32>>().unwrap_err();
+        assert_eq!(err, "4x2".parse::<i32>().unwrap_err());
+        assert!("".parse::<MiniBox<f64>>().is_err());
+    }
+
+    #[test]
+    fn string_write_extend() {
+        use core::fmt::Write;
+        use std::string::String;
+        use std::vec::Vec;
+
+        let mut bx = MiniBox::new(String::new());
+        let name = "a";
+        write!(bx, "{}-{:02}", name, 7).unwrap();
+        bx.write_char('!').unwrap();
+        assert_eq!(*bx, "a-07!");
+
+        bx.extend("xyz".chars().rev());
+        bx.extend(["1", "2"]);
+        bx.extend(&['3', '4']);
+        assert_eq!(*bx, "a-07!zyx1234");
+
+        let mut bx = MiniBox::new(Vec::new());
+        bx.extend(0..3_u8);
+        assert_eq!(*bx, [0, 1, 2]);
+    }
+
+    #[test]
+    fn hash_map_str_keys() {
+        use std::boxed::Box;
+        use std::collections::HashMap;
+        use std::string::String;
+
+        let mut map = HashMap::new();
+        map.insert(MiniBox::new(String::from("hello")), 1);
+        map.insert(MiniBox::new(String::from("world")), 2);
+        assert_eq!(map.get("hello"), Some(&1));
+        assert_eq!(map.get("world"), Some(&2));
+        assert_eq!(map.get("hello world"), None);
+        assert_eq!(map.remove("hello"), Some(1));
+        assert!(!map.contains_key("hello"));
+
+        let mut map = HashMap::new();
+        map.insert(MiniBox::new(Box::<str>::from("hello")), 1);
+        assert_eq!(map.get("hello"), Some(&1));
+        assert_eq!(map.get("world"), None);
+    }
+
+    #[test]
+    fn test_uninitialized_minibox_new() {
+        type Uninint = core::mem::MaybeUninit<usize>;
+
+        let _sto = MiniBox::new(Uninint::uninit());
+    }
+
+    #[test]
+    fn miniptr_send() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MiniPtr<u32>>();
+        assert_send_sync::<MiniPtr<[u64; 4]>>();
+
+        let small = MiniBox::into_ptr(MiniBox::new(10_u32));
+        let large = MiniBox::into_ptr(MiniBox::new([1_u64; 4]));
+
+        let (small, large) = std::thread::spawn(move || {
+            let small = unsafe { MiniBox::from_ptr(small) };
+            let large = unsafe { MiniBox::from_ptr(large) };
+            (MiniBox::into_inner(small), MiniBox::into_inner(large))
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(small, 10);
+        assert_eq!(large, [1; 4]);
+    }
+
+    #[test]
+    fn test_ref_from_miniptr_small() {
+        let value: u16 = 173;
+        let storage = MiniBox::into_ptr(MiniBox::new(value));
+
+        {
+            let value_ref_1: &u16 = unsafe { storage.as_ref() };
+            let value_ref_2: &u16 = unsafe { storage.as_ref() };
+
+            assert_eq!(*value_ref_1, 173);
+            assert_eq!(*value_ref_2, 173);
+        }
+
+        // drop stowed
+        unsafe { MiniBox::from_ptr(storage) };
+    }
+
+    #[test]
+    fn test_ref_from_miniptr_large() {
+        use std::vec::Vec;
+
+        let value: Vec<i64> = vec![3245, 5675, 4653, 1234, 7345];
+
+        let storage = MiniBox::into_ptr(MiniBox::new(value));
+
+        {
+            let value_ref_1: &Vec<i64> = unsafe { storage.as_ref() };
+            let value_ref_2: &Vec<i64> = unsafe { storage.as_ref() };
+
+            assert_eq!(**value_ref_1, [3245, 5675, 4653, 1234, 7345]);
+            assert_eq!(**value_ref_2, [3245, 5675, 4653, 1234, 7345]);
+        }
+
+        // drop stowed
+        unsafe { MiniBox::from_ptr(storage) };
+    }
+}
+
+#[cfg(test)]
+mod test_pin {
+    use crate::MiniBox;
+    use core::future::Future;
+    use core::marker::PhantomPinned;
+    use core::pin::Pin;
+    use core::ptr::NonNull;
+    use core::task::{Context, Poll, Waker};
+
+    /// A self-referential future that records its own address when it is first polled,
+    /// and asserts that it hasn't moved on every later poll, and when it is dropped
+    struct AddrGuard {
+        addr: Option<NonNull<AddrGuard>>,
+        _pin: PhantomPinned,
+    }
+
+    impl AddrGuard {
+        fn new() -> Self {
+            Self {
+                addr: None,
+                _pin: PhantomPinned,
+            }
+        }
+    }
+
+    impl Future for AddrGuard {
+        type Output = usize;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+            let this = unsafe { self.get_unchecked_mut() };
+            let addr = NonNull::from(&mut *this);
+
+            match this.addr {
+                None => {
+                    this.addr = Some(addr);
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                Some(old) => {
+                    assert_eq!(old, addr, "the future was moved after it was pinned");
+                    Poll::Ready(addr.as_ptr() as usize)
+                }
+            }
+        }
+    }
+
+    impl Drop for AddrGuard {
+        fn drop(&mut self) {
+            if let Some(old) = self.addr {
+                assert_eq!(
+                    old,
+                    NonNull::from(&mut *self),
+                    "the future was moved before it was dropped"
+                );
+            }
+        }
+    }
+
+    struct BigAddrGuard {
+        inner: AddrGuard,
+        _padding: [usize; 4],
+    }
+
+    impl Future for BigAddrGuard {
+        type Output = usize;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+            unsafe { self.map_unchecked_mut(|this| &mut this.inner) }.poll(cx)
+        }
+    }
+
+    fn poll_twice<F: Future>(mut fut: Pin<&mut F>) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+
+        match fut.poll(&mut cx) {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("the future should be complete"),
+        }
+    }
+
+    #[test]
+    fn inline_future() {
+        assert_eq!(MiniBox::<AddrGuard>::SIZE_CLASS, crate::SizeClass::Inline);
+
+        let mut bx = MiniBox::new(AddrGuard::new());
+        let bx = unsafe { Pin::new_unchecked(&mut bx) };
+
+        let addr = &**bx as *const AddrGuard as usize;
+        assert_eq!(poll_twice(bx), addr);
+    }
+
+    #[test]
+    fn boxed_future() {
+        assert_eq!(MiniBox::<BigAddrGuard>::SIZE_CLASS, crate::SizeClass::Boxed);
+
+        let mut bx = MiniBox::pin(BigAddrGuard {
+            inner: AddrGuard::new(),
+            _padding: [0; 4],
+        });
+        let addr = &bx.inner as *const AddrGuard as usize;
+        assert_eq!(poll_twice(bx.as_mut()), addr);
+
+        // moving the pinned box doesn't move the future
+        let bx = core::convert::identity(bx);
+        assert_eq!(&bx.inner as *const AddrGuard as usize, addr);
+    }
+
+    #[test]
+    fn pin_from() {
+        let fut: Pin<MiniBox<_>> = MiniBox::new(core::future::ready([1_u64; 4])).into();
+        assert_eq!(
+            MiniBox::<core::future::Ready<[u64; 4]>>::SIZE_CLASS,
+            crate::SizeClass::Boxed
+        );
+
+        let mut fut = fut;
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready([1; 4]));
+    }
+
+    #[test]
+    fn project_pin() {
+        struct Pair {
+            first: AddrGuard,
+            second: BigAddrGuard,
+        }
+
+        let mut bx = MiniBox::pin(Pair {
+            first: AddrGuard::new(),
+            second: BigAddrGuard {
+                inner: AddrGuard::new(),
+                _padding: [0; 4],
+            },
+        });
+
+        let first_addr = &bx.first as *const AddrGuard as usize;
+        let second_addr = &bx.second.inner as *const AddrGuard as usize;
+
+        let (first, second) = unsafe { crate::project_pin!(bx.as_mut() => first, second) };
+        assert_eq!(poll_twice(first), first_addr);
+        assert_eq!(poll_twice(second), second_addr);
+    }
+
+    #[test]
+    fn project_pin_shadowed_core() {
+        // `project_pin!` must not depend on what `core` resolves to at the call site
+        #[allow(dead_code)]
+        mod core {}
+
+        struct Wrapper {
+            guard: BigAddrGuard,
+        }
+
+        let mut bx = MiniBox::pin(Wrapper {
+            guard: BigAddrGuard {
+                inner: AddrGuard::new(),
+                _padding: [0; 4],
+            },
+        });
+        let addr = &bx.guard.inner as *const AddrGuard as usize;
+
+        let (guard,) = unsafe { crate::project_pin!(bx.as_mut() => guard) };
+        assert_eq!(poll_twice(guard), addr);
+    }
+
+    /// Returns `Pending` the first time it's polled
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if core::mem::replace(&mut self.0, true) {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    /// An `async fn` (so `!Unpin`) that holds a reference to one of its own locals across an `.await`
+    async fn self_referential(seen: &core::cell::Cell<usize>) -> u64 {
+        let values = [1_u64, 2, 3, 4];
+        let first = &values[0];
+        seen.set(first as *const u64 as usize);
+        YieldOnce(false).await;
+        assert_eq!(seen.get(), first as *const u64 as usize);
+        values.iter().sum::<u64>() + *first
+    }
+
+    fn size_class_of<T>(_: &T) -> crate::SizeClass {
+        MiniBox::<T>::SIZE_CLASS
+    }
+
+    fn block_on<F: Future>(mut fut: Pin<&mut F>) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(value) = fut.as_mut().poll(&mut cx) {
+                return value;
+            }
+        }
+    }
+
+    #[test]
+    fn async_block() {
+        let seen = core::cell::Cell::new(0);
+
+        let fut = self_referential(&seen);
+        // the locals held across the `.await` make it too big to store inline
+        assert_eq!(size_class_of(&fut), crate::SizeClass::Boxed);
+
+        // pinned on the heap, and moved between polls
+        let mut bx = MiniBox::pin(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(bx.as_mut().poll(&mut cx).is_pending());
+        let mut bx = core::convert::identity(bx);
+        assert_eq!(bx.as_mut().poll(&mut cx), Poll::Ready(11));
+
+        // driven through `MiniBox`'s own `Future` impl (i.e. `deref_pin_mut`)
+        let bx = core::pin::pin!(MiniBox::new(self_referential(&seen)));
+        assert_eq!(block_on(bx), 11);
+
+        // small enough to be stored inline, so it is pinned by pinning the `MiniBox`
+        let fut = async {
+            YieldOnce(false).await;
+            7_u8
+        };
+        assert_eq!(size_class_of(&fut), crate::SizeClass::Inline);
+        let bx = core::pin::pin!(MiniBox::new(fut));
+        assert_eq!(block_on(bx), 7);
+    }
+
+    #[test]
+    fn pinned_inline_self_reference() {
+        // meant to be run under Miri
+        struct SelfRef {
+            this: *const SelfRef,
+            _pin: PhantomPinned,
+        }
+
+        impl SelfRef {
+            fn init(self: Pin<&mut Self>) {
+                let this = unsafe { self.get_unchecked_mut() };
+                this.this = this;
+            }
+
+            fn check(self: Pin<&Self>) {
+                assert!(core::ptr::eq(self.this, &*self));
+            }
+        }
+
+        assert_eq!(MiniBox::<SelfRef>::SIZE_CLASS, crate::SizeClass::Inline);
+
+        // the value moves into the `MiniBox`, and then the `MiniBox` moves into the pin,
+        // neither of which matters because the self reference is only created after pinning
+        let mut bx = core::pin::pin!(MiniBox::new(SelfRef {
+            this: core::ptr::null(),
+            _pin: PhantomPinned,
+        }));
+        MiniBox::deref_pin_mut(bx.as_mut()).init();
+
+        for _ in 0..4 {
+            MiniBox::deref_pin_mut(bx.as_mut()).as_ref().check();
+        }
+    }
+
+    #[test]
+    fn map_unchecked_pin() {
+        struct Outer {
+            id: u32,
+            fut: BigAddrGuard,
+        }
+
+        let bx = core::pin::pin!(MiniBox::new(Outer {
+            id: 3,
+            fut: BigAddrGuard {
+                inner: AddrGuard::new(),
+                _padding: [0; 4],
+            },
+        }));
+        assert_eq!(MiniBox::<Outer>::SIZE_CLASS, crate::SizeClass::Boxed);
+        let addr = &bx.fut.inner as *const AddrGuard as usize;
+        assert_eq!(bx.id, 3);
+
+        let fut = unsafe { MiniBox::map_unchecked_pin(bx, |outer| &mut outer.fut) };
+        assert_eq!(poll_twice(fut), addr);
+
+        // inline values are pinned by pinning the `MiniBox` itself
+        struct Wrapper(AddrGuard);
+
+        let bx = core::pin::pin!(MiniBox::new(Wrapper(AddrGuard::new())));
+        assert_eq!(MiniBox::<Wrapper>::SIZE_CLASS, crate::SizeClass::Inline);
+        let fut = unsafe { MiniBox::map_unchecked_pin(bx, |wrapper| &mut wrapper.0) };
+        let addr = &*fut as *const AddrGuard as usize;
+        assert_eq!(poll_twice(fut), addr);
+    }
+
+    #[test]
+    fn unpin() {
+        fn assert_unpin<T: Unpin>() {}
+
+        assert_unpin::<MiniBox<()>>();
+        assert_unpin::<MiniBox<u8>>();
+        assert_unpin::<MiniBox<[u64; 4]>>();
+        assert_unpin::<crate::MiniPtr<[u64; 4]>>();
+
+        // a boxed `!Unpin` value isn't `Unpin` in a `MiniBox` (unlike in a `Box`),
+        // so it has to be pinned with `MiniBox::pin`, which doesn't need `Unpin`
+        assert_unpin::<std::boxed::Box<BigAddrGuard>>();
+        let bx = MiniBox::pin(BigAddrGuard {
+            inner: AddrGuard::new(),
+            _padding: [0; 4],
+        });
+        let mut moved = core::convert::identity(bx);
+        let addr = &moved.inner as *const AddrGuard as usize;
+        assert_eq!(poll_twice(moved.as_mut()), addr);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(not(miri))]
+    fn pin_inline() {
+        drop(MiniBox::pin(AddrGuard::new()));
+    }
+}
+
+// these tests use `std` APIs such as `std::panic` and `std::thread`
+#[cfg(all(test, feature = "std"))]
+mod test_drop {
+    use crate::MiniBox;
+    use core::cell::Cell;
+    use core::mem;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    struct DropCounter<'a> {
+        counter: &'a Cell<u32>,
+    }
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.counter.set(self.counter.get() + 1);
+        }
+    }
+
+    #[test]
+    fn mini_ptr_round_trip() {
+        use crate::MiniPtr;
+
+        let counter = Cell::new(0);
+
+        let ptr: MiniPtr<DropCounter> = MiniBox::new(DropCounter { counter: &counter }).into();
+        assert_eq!(counter.get(), 0);
+        let bx = unsafe { ptr.into_box() };
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 1);
+
+        let ptr = MiniPtr::from(MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]));
+        assert_eq!(counter.get(), 1);
+        let bx = unsafe { ptr.into_box() };
+        assert_eq!(counter.get(), 1);
+        drop(bx);
+        assert_eq!(counter.get(), 4);
+    }
+
+    #[test]
+    fn into_inner_drops_once() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let counter = Cell::new(0);
+
+        let value = MiniBox::into_inner(MiniBox::new(DropCounter { counter: &counter }));
+        assert_eq!(counter.get(), 0);
+        drop(value);
+        assert_eq!(counter.get(), 1);
+
+        // panic while the extracted inline value is alive
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let bx = MiniBox::new(DropCounter { counter: &counter });
+            let _value = MiniBox::into_inner(bx);
+            panic!()
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 2);
+
+        // panic while the value is being transformed
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let bx = MiniBox::new(DropCounter { counter: &counter });
+            MiniBox::map(bx, |_value| -> u8 { panic!() })
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 3);
+
+        // the same for a boxed value
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let bx = MiniBox::new([
+                DropCounter { counter: &counter },
+                DropCounter { counter: &counter },
+            ]);
+            MiniBox::map(bx, |_value| -> u8 { panic!() })
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 5);
+    }
+
+    #[test]
+    fn uninit_array() {
+        let counter = Cell::new(0);
+
+        let mut bx = MiniBox::<DropCounter>::new_uninit_array::<4>();
+        for slot in bx.iter_mut() {
+            slot.write(DropCounter { counter: &counter });
+        }
+        let bx = unsafe { MiniBox::assume_init_array(bx) };
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 4);
+
+        // inline
+        let mut bx = MiniBox::<u8>::new_uninit_array::<4>();
+        for (i, slot) in bx.iter_mut().enumerate() {
+            slot.write(i as u8);
+        }
+        assert_eq!(*unsafe { MiniBox::assume_init_array(bx) }, [0, 1, 2, 3]);
+
+        // large boxed array
+        let mut bx = MiniBox::<u64>::new_uninit_array::<1024>();
+        for (i, slot) in bx.iter_mut().enumerate() {
+            slot.write(i as u64);
+        }
+        let bx = unsafe { MiniBox::assume_init_array(bx) };
+        assert!(bx.iter().enumerate().all(|(i, &x)| x == i as u64));
+
+        // abandoning a partially initialized array doesn't drop anything
+        let mut bx = MiniBox::<DropCounter>::new_uninit_array::<4>();
+        bx[0].write(DropCounter { counter: &counter });
+        bx[1].write(DropCounter { counter: &counter });
+        drop(bx);
+        assert_eq!(counter.get(), 4);
+    }
+
+    #[test]
+    fn drop_uninit() {
+        // meant to be run under Miri, which reports any read of the uninitialized value and any leak
+        struct PanicOnDrop {
+            _padding: [u64; 8],
+        }
+
+        impl Drop for PanicOnDrop {
+            fn drop(&mut self) {
+                panic!("an uninitialized value was dropped")
+            }
+        }
+
+        assert_eq!(MiniBox::<PanicOnDrop>::SIZE_CLASS, crate::SizeClass::Boxed);
+        drop(MiniBox::<PanicOnDrop>::new_uninit());
+        drop(MiniBox::<PanicOnDrop>::new_zeroed());
+        drop(MiniBox::<PanicOnDrop>::new_uninit_array::<3>());
 
-        assert!(matches!(SizeClass::new::<usize>(), SizeClass::Inline));
-        assert!(matches!(SizeClass::new::<[usize; 2]>(), SizeClass::Boxed));
+        // a partially initialized value isn't dropped either
+        let counter = Cell::new(0);
+        let mut bx = MiniBox::<(DropCounter, [u64; 4])>::new_uninit();
+        unsafe {
+            core::ptr::addr_of_mut!((*bx.as_mut_ptr()).0).write(DropCounter { counter: &counter })
+        }
+        drop(bx);
+        assert_eq!(counter.get(), 0);
+
+        // inline
+        drop(MiniBox::<DropCounter>::new_uninit());
+        assert_eq!(counter.get(), 0);
     }
 
     #[test]
-    #[should_panic]
-    #[cfg(not(miri))]
-    pub fn nonzerosized_const() {
-        MiniBox::new_zst(0);
+    fn into_uninit() {
+        let counter = Cell::new(0);
+
+        // inline
+        let bx = MiniBox::new(DropCounter { counter: &counter });
+        let storage = MiniBox::into_uninit(bx);
+        let value = unsafe { storage.as_ptr().read() };
+        assert_eq!(counter.get(), 0);
+        drop(value);
+        assert_eq!(counter.get(), 1);
+
+        drop(storage);
+        assert_eq!(counter.get(), 1);
+
+        // boxed, take the value out and write a new one into the same storage
+        let bx = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        let address = &*bx as *const [DropCounter; 2] as usize;
+        let mut storage = MiniBox::into_uninit(bx);
+        let [a, b] = unsafe { storage.as_ptr().read() };
+        unsafe {
+            storage.as_mut_ptr().write([b, a]);
+        }
+        let bx = unsafe { storage.assume_init() };
+        assert_eq!(&*bx as *const [DropCounter; 2] as usize, address);
+        assert_eq!(counter.get(), 1);
+        drop(bx);
+        assert_eq!(counter.get(), 3);
+
+        // dropping the storage leaks the value
+        drop(MiniBox::into_uninit(MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ])));
+        assert_eq!(counter.get(), 3);
     }
 
     #[test]
-    pub fn zst() {
-        let bx = MiniBox::new_zst(());
-        assert_eq!(*bx, ());
+    fn builder() {
+        use crate::MiniBoxBuilder;
+        use core::ptr::addr_of_mut;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
 
-        let bx = MiniBox::new_zst(OverAlignedZeroSized);
-        assert!(matches!(*bx, OverAlignedZeroSized));
+        struct Fields<'a> {
+            a: DropCounter<'a>,
+            b: [u64; 4],
+            c: DropCounter<'a>,
+        }
+
+        let a_counter = Cell::new(0);
+        let c_counter = Cell::new(0);
+
+        let mut builder = MiniBoxBuilder::<Fields>::new();
+        unsafe {
+            builder.init_field(
+                |ptr| addr_of_mut!((*ptr).c),
+                DropCounter {
+                    counter: &c_counter,
+                },
+            );
+            builder.init_field(|ptr| addr_of_mut!((*ptr).b), [1; 4]);
+            builder.init_field(
+                |ptr| addr_of_mut!((*ptr).a),
+                DropCounter {
+                    counter: &a_counter,
+                },
+            );
+        }
+        let bx = unsafe { builder.build() };
+        assert_eq!(bx.b, [1; 4]);
+        assert_eq!((a_counter.get(), c_counter.get()), (0, 0));
+        drop(bx);
+        assert_eq!((a_counter.get(), c_counter.get()), (1, 1));
+
+        fn fail() -> [u64; 4] {
+            panic!()
+        }
+
+        // panic after the first field is initialized
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let mut builder = MiniBoxBuilder::<Fields>::new();
+            unsafe {
+                builder.init_field(
+                    |ptr| addr_of_mut!((*ptr).a),
+                    DropCounter {
+                        counter: &a_counter,
+                    },
+                );
+                builder.init_field(|ptr| addr_of_mut!((*ptr).b), fail());
+            }
+        }));
+        assert!(result.is_err());
+        assert_eq!((a_counter.get(), c_counter.get()), (2, 1));
+
+        // abandoned builder for an inline type that was moved after a field was initialized
+        assert!(matches!(
+            MiniBox::<(DropCounter,)>::SIZE_CLASS,
+            crate::SizeClass::Inline
+        ));
+        let mut builder = MiniBoxBuilder::<(DropCounter,)>::new();
+        unsafe {
+            builder.init_field(
+                |ptr| addr_of_mut!((*ptr).0),
+                DropCounter {
+                    counter: &a_counter,
+                },
+            );
+        }
+        let builder = [builder];
+        drop(builder);
+        assert_eq!(a_counter.get(), 3);
     }
 
     #[test]
-    pub fn overaligned() {
-        let bx = MiniBox::new(OverAlignedByte(3));
-        assert_eq!(bx.0, 3);
+    fn collect_array() {
+        use std::vec::Vec;
+
+        let counter = Cell::new(0);
+        let items = |n| (0..n).map(|_| DropCounter { counter: &counter });
+
+        // exact
+        let bx = crate::collect_array::<_, _, 3>(items(3)).unwrap();
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 3);
+
+        // short, the collected items are dropped
+        assert!(crate::collect_array::<_, _, 3>(items(2)).is_none());
+        assert_eq!(counter.get(), 5);
+
+        // long, the collected items and the extra item that was taken are dropped
+        let mut iter = items(6).collect::<Vec<_>>().into_iter();
+        assert!(crate::collect_array::<_, _, 3>(iter.by_ref()).is_none());
+        assert_eq!(counter.get(), 9);
+        assert_eq!(iter.len(), 2);
+        drop(iter);
+        assert_eq!(counter.get(), 11);
+
+        // large boxed array
+        let bx = crate::collect_array::<u64, _, 512>(0..512).unwrap();
+        assert!(bx.iter().enumerate().all(|(i, &x)| x == i as u64));
+
+        let bx = crate::collect_array::<u8, _, 0>(core::iter::empty()).unwrap();
+        assert_eq!(*bx, []);
     }
 
     #[test]
-    pub fn boxed() {
-        let bx = MiniBox::new([3_u8; 32]);
-        assert_eq!(*bx, [3; 32]);
+    fn drop_address() {
+        // records the address it had when `record` was called, and the address it had when dropped
+        struct AddrCheck<'a, P> {
+            log: &'a Cell<(usize, usize)>,
+            _padding: P,
+        }
 
-        let bx = MiniBox::<[u8; 32]>::zeroed();
-        assert_eq!(*bx, [0; 32]);
+        impl<P> AddrCheck<'_, P> {
+            fn record(&self) {
+                self.log.set((self as *const Self as usize, 0));
+            }
+        }
+
+        impl<P> Drop for AddrCheck<'_, P> {
+            fn drop(&mut self) {
+                let (recorded, _) = self.log.get();
+                self.log.set((recorded, self as *const Self as usize));
+            }
+        }
+
+        #[inline(never)]
+        fn moved<T>(value: T) -> T {
+            value
+        }
+
+        let log = Cell::new((0, 0));
+
+        // inline, the value moves along with the box
+        let bx = MiniBox::new(AddrCheck {
+            log: &log,
+            _padding: (),
+        });
+        assert_eq!(
+            MiniBox::<AddrCheck<()>>::SIZE_CLASS,
+            crate::SizeClass::Inline
+        );
+        bx.record();
+        let bx = moved([bx]);
+        drop(bx);
+        let (recorded, dropped) = log.get();
+        assert_ne!(recorded, dropped);
+
+        // boxed, the value's address is stable across moves
+        let bx = MiniBox::new(AddrCheck {
+            log: &log,
+            _padding: [0_u64; 2],
+        });
+        assert_eq!(
+            MiniBox::<AddrCheck<[u64; 2]>>::SIZE_CLASS,
+            crate::SizeClass::Boxed
+        );
+        bx.record();
+        let bx = moved([bx]);
+        drop(bx);
+        let (recorded, dropped) = log.get();
+        assert_eq!(recorded, dropped);
     }
 
     #[test]
-    pub fn inline() {
-        let bx = MiniBox::new(3_u8);
-        assert_eq!(*bx, 3);
+    fn try_map() {
+        let counter = Cell::new(0);
 
-        let bx = MiniBox::<u8>::zeroed();
-        assert_eq!(*bx, 0);
+        // inline -> boxed
+        let bx = MiniBox::new(DropCounter { counter: &counter });
+        let bx = MiniBox::try_map(bx, |value| {
+            Ok::<_, ()>([value, DropCounter { counter: &counter }])
+        })
+        .unwrap();
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 2);
+
+        // boxed -> inline
+        let bx = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        let bx = MiniBox::try_map(bx, |[a, b]| {
+            drop(b);
+            Ok::<_, ()>(a)
+        })
+        .unwrap();
+        assert_eq!(counter.get(), 3);
+        drop(bx);
+        assert_eq!(counter.get(), 4);
+
+        // boxed, error path
+        let bx = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        let err = MiniBox::try_map(bx, Err::<u8, _>).unwrap_err();
+        assert_eq!(counter.get(), 4);
+        drop(err);
+        assert_eq!(counter.get(), 6);
+
+        // inline, error path that drops the value
+        let bx = MiniBox::new(DropCounter { counter: &counter });
+        assert!(MiniBox::try_map(bx, |_| Err::<[u64; 4], _>(())).is_err());
+        assert_eq!(counter.get(), 7);
     }
 
     #[test]
-    pub fn word() {
-        let bx = MiniBox::new(3_usize);
-        assert_eq!(*bx, 3);
+    fn map() {
+        let counter = Cell::new(0);
 
-        let bx = MiniBox::<usize>::zeroed();
-        assert_eq!(*bx, 0);
+        let bx = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        let bx = MiniBox::map(bx, |[a, _]| a);
+        assert_eq!(counter.get(), 1);
+        drop(bx);
+        assert_eq!(counter.get(), 2);
     }
 
     #[test]
-    fn test_uninitialized_minibox_new() {
-        type Uninint = core::mem::MaybeUninit<usize>;
+    fn flatten() {
+        let counter = Cell::new(0);
 
-        let _sto = MiniBox::new(Uninint::uninit());
+        // inline in inline
+        let bx = MiniBox::new(MiniBox::new(DropCounter { counter: &counter }));
+        assert_eq!(MiniBox::<DropCounter>::SIZE_CLASS, crate::SizeClass::Inline);
+        let bx = MiniBox::flatten(bx);
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 1);
+
+        // boxed in boxed
+        let bx = MiniBox::new(MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]));
+        assert_eq!(
+            MiniBox::<[DropCounter; 2]>::SIZE_CLASS,
+            crate::SizeClass::Boxed
+        );
+        let addr = &**bx as *const [DropCounter; 2];
+        let bx = MiniBox::flatten(bx);
+        assert_eq!(&*bx as *const [DropCounter; 2], addr);
+        assert_eq!(counter.get(), 1);
+        drop(bx);
+        assert_eq!(counter.get(), 3);
+
+        // flattening twice
+        let bx = MiniBox::new(MiniBox::new(MiniBox::new(DropCounter {
+            counter: &counter,
+        })));
+        let bx = MiniBox::flatten(MiniBox::flatten(bx));
+        assert_eq!(counter.get(), 3);
+        drop(bx);
+        assert_eq!(counter.get(), 4);
     }
 
     #[test]
-    fn test_ref_from_miniptr_small() {
-        let value: u16 = 173;
-        let storage = MiniBox::into_ptr(MiniBox::new(value));
+    fn mini_option() {
+        use crate::MiniOption;
 
-        {
-            let value_ref_1: &u16 = unsafe { storage.as_ref() };
-            let value_ref_2: &u16 = unsafe { storage.as_ref() };
+        let counter = Cell::new(0);
 
-            assert_eq!(*value_ref_1, 173);
-            assert_eq!(*value_ref_2, 173);
+        // boxed
+        let opt = MiniOption::some((DropCounter { counter: &counter }, 0_u64));
+        drop(opt);
+        assert_eq!(counter.get(), 1);
+
+        // stored inline next to the tag byte
+        static SMALL_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        struct SmallDrop(u8);
+
+        impl Drop for SmallDrop {
+            fn drop(&mut self) {
+                SMALL_COUNTER.fetch_add(1, Ordering::SeqCst);
+            }
         }
 
-        // drop stowed
-        unsafe { MiniBox::from_ptr(storage) };
+        let mut opt = MiniOption::some(SmallDrop(1));
+        assert!(opt.replace(SmallDrop(2)).is_some());
+        assert_eq!(SMALL_COUNTER.load(Ordering::SeqCst), 1);
+        assert_eq!(opt.as_ref().map(|x| x.0), Some(2));
+        drop(opt);
+        assert_eq!(SMALL_COUNTER.load(Ordering::SeqCst), 2);
+
+        let mut opt = MiniOption::some((DropCounter { counter: &counter }, 0_u8));
+        assert!(opt
+            .replace((DropCounter { counter: &counter }, 1))
+            .is_some());
+        assert_eq!(counter.get(), 2);
+        let value = opt.take();
+        assert_eq!(counter.get(), 2);
+        drop(value);
+        assert_eq!(counter.get(), 3);
+        drop(opt);
+        assert_eq!(counter.get(), 3);
+
+        let opt = MiniOption::some([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        let value = opt.into_option();
+        assert_eq!(counter.get(), 3);
+        drop(value);
+        assert_eq!(counter.get(), 5);
     }
 
     #[test]
-    fn test_ref_from_miniptr_large() {
-        use std::vec::Vec;
+    fn dyn_mini_box() {
+        use crate::DynMiniBox;
+        use core::fmt::{self, Display};
+        use std::string::ToString;
 
-        let value: Vec<i64> = vec![3245, 5675, 4653, 1234, 7345];
+        struct Shout<'a> {
+            _counter: DropCounter<'a>,
+            text: &'static str,
+        }
 
-        let storage = MiniBox::into_ptr(MiniBox::new(value));
+        impl Display for Shout<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}!", self.text)
+            }
+        }
 
-        {
-            let value_ref_1: &Vec<i64> = unsafe { storage.as_ref() };
-            let value_ref_2: &Vec<i64> = unsafe { storage.as_ref() };
+        let counter = Cell::new(0);
 
-            assert_eq!(**value_ref_1, [3245, 5675, 4653, 1234, 7345]);
-            assert_eq!(**value_ref_2, [3245, 5675, 4653, 1234, 7345]);
-        }
+        let bx: DynMiniBox<dyn Display> = crate::dyn_minibox!(Shout {
+            _counter: DropCounter { counter: &counter },
+            text: "hello",
+        });
+        assert_eq!(bx.to_string(), "hello!");
+        assert_eq!(counter.get(), 0);
+        drop(bx);
+        assert_eq!(counter.get(), 1);
 
-        // drop stowed
-        unsafe { MiniBox::from_ptr(storage) };
-    }
-}
+        let bx = DynMiniBox::<dyn Display>::from_box(std::boxed::Box::new(Shout {
+            _counter: DropCounter { counter: &counter },
+            text: "hi",
+        }));
+        let bx = DynMiniBox::into_box(bx);
+        assert_eq!(bx.to_string(), "hi!");
+        drop(bx);
+        assert_eq!(counter.get(), 2);
 
-#[cfg(test)]
-mod test_drop {
-    use crate::MiniBox;
-    use core::cell::Cell;
-    use core::mem;
-    use core::sync::atomic::{AtomicU32, Ordering};
+        let mut bx: DynMiniBox<[DropCounter]> = crate::dyn_minibox!([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+        bx.swap(0, 2);
+        assert_eq!(bx.len(), 3);
+        drop(bx);
+        assert_eq!(counter.get(), 5);
 
-    struct DropCounter<'a> {
-        counter: &'a Cell<u32>,
+        #[cfg(feature = "nightly")]
+        {
+            let bx = DynMiniBox::<dyn Display>::new(Shout {
+                _counter: DropCounter { counter: &counter },
+                text: "hey",
+            });
+            assert_eq!(bx.to_string(), "hey!");
+            drop(bx);
+            assert_eq!(counter.get(), 6);
+        }
     }
 
-    impl<'a> Drop for DropCounter<'a> {
-        fn drop(&mut self) {
-            self.counter.set(self.counter.get() + 1);
+    #[test]
+    fn mini_cow() {
+        use crate::MiniCow;
+
+        struct Counted<'a>(DropCounter<'a>, [u64; 4]);
+
+        impl Clone for Counted<'_> {
+            fn clone(&self) -> Self {
+                Counted(
+                    DropCounter {
+                        counter: self.0.counter,
+                    },
+                    self.1,
+                )
+            }
         }
+
+        let counter = Cell::new(0);
+        let value = Counted(DropCounter { counter: &counter }, [1; 4]);
+
+        let mut cow = MiniCow::from_borrowed(&value);
+        assert!(cow.is_borrowed());
+        assert_eq!(cow.1, [1; 4]);
+
+        cow.to_mut().1[0] = 2;
+        assert!(cow.is_owned());
+        assert_eq!(cow.1, [2, 1, 1, 1]);
+        assert_eq!(value.1, [1; 4]);
+
+        drop(cow);
+        assert_eq!(counter.get(), 1);
+
+        let owned = MiniCow::from_borrowed(&value).into_owned();
+        assert_eq!(owned.1, [1; 4]);
+        drop(owned);
+        assert_eq!(counter.get(), 2);
+
+        let cow = MiniCow::from_owned(value.clone());
+        let owned = cow.into_owned();
+        assert_eq!(counter.get(), 2);
+        drop(owned);
+        assert_eq!(counter.get(), 3);
+
+        drop(value);
+        assert_eq!(counter.get(), 4);
     }
 
     #[test]
@@ -705,6 +4348,136 @@ mod test_drop {
         assert_eq!(counter.get(), 16);
     }
 
+    #[test]
+    fn from_fn_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let counter: Cell<u32> = Cell::new(0);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            MiniBox::<[DropCounter; 16]>::from_fn(|i| {
+                if i == 4 {
+                    panic!("fifth element");
+                }
+
+                DropCounter { counter: &counter }
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 4);
+    }
+
+    #[test]
+    fn copy_storage_word() {
+        let counter: Cell<u32> = Cell::new(0);
+
+        let small = MiniBox::new(DropCounter { counter: &counter });
+        let large = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ]);
+
+        let small_ptr = unsafe { MiniBox::copy_storage_word(&small) };
+        let large_ptr = unsafe { MiniBox::copy_storage_word(&large) };
+
+        unsafe {
+            assert!(core::ptr::eq(small_ptr.as_ref().counter, &counter));
+            assert!(core::ptr::eq(&*large, large_ptr.as_ref()));
+        }
+
+        drop(small);
+        drop(large);
+        assert_eq!(counter.get(), 3);
+    }
+
+    #[test]
+    fn into_arc() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let counter: Cell<u32> = Cell::new(0);
+
+        let arc = MiniBox::into_arc(MiniBox::new(DropCounter { counter: &counter }));
+        let other = Arc::clone(&arc);
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(arc);
+        assert_eq!(counter.get(), 0);
+        drop(other);
+        assert_eq!(counter.get(), 1);
+
+        let rc: Rc<[DropCounter; 2]> = MiniBox::new([
+            DropCounter { counter: &counter },
+            DropCounter { counter: &counter },
+        ])
+        .into();
+        let other = Rc::clone(&rc);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(rc);
+        assert_eq!(counter.get(), 1);
+        drop(other);
+        assert_eq!(counter.get(), 3);
+
+        let arc: Arc<[u64; 4]> = MiniBox::new([1; 4]).into();
+        assert_eq!(*arc, [1; 4]);
+    }
+
+    #[test]
+    fn clone_array() {
+        use std::string::{String, ToString};
+
+        let bx = MiniBox::<[String; 1024]>::from_fn(|i| i.to_string());
+        let clone = MiniBox::clone_array(&bx);
+        assert_eq!(*bx, *clone);
+        assert!(!MiniBox::ptr_eq(&bx, &clone));
+
+        let bx = MiniBox::new([1_u8, 2, 3]);
+        assert_eq!(*MiniBox::clone_array(&bx), [1, 2, 3]);
+    }
+
+    #[test]
+    fn clone_array_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct PanicOnClone<'a> {
+            clones: &'a Cell<u32>,
+            counter: &'a Cell<u32>,
+        }
+
+        impl Clone for PanicOnClone<'_> {
+            fn clone(&self) -> Self {
+                if self.clones.get() == 4 {
+                    panic!("fifth clone");
+                }
+
+                self.clones.set(self.clones.get() + 1);
+                Self { ..*self }
+            }
+        }
+
+        impl Drop for PanicOnClone<'_> {
+            fn drop(&mut self) {
+                self.counter.set(self.counter.get() + 1);
+            }
+        }
+
+        let clones = Cell::new(0);
+        let counter = Cell::new(0);
+
+        let bx = MiniBox::<[PanicOnClone; 8]>::from_fn(|_| PanicOnClone {
+            clones: &clones,
+            counter: &counter,
+        });
+
+        let result = catch_unwind(AssertUnwindSafe(|| MiniBox::clone_array(&bx)));
+        assert!(result.is_err());
+        assert_eq!(clones.get(), 4);
+        assert_eq!(counter.get(), 4);
+
+        drop(bx);
+        assert_eq!(counter.get(), 12);
+    }
+
     #[test]
     fn large_raw_stowed_value() {
         let counter: Cell<u32> = Cell::new(0);
@@ -747,3 +4520,93 @@ mod test_drop {
         assert_eq!(counter.get(), 16);
     }
 }
+
+/// Where values live, and when their address changes
+#[cfg(test)]
+mod test_addr {
+    use crate::{MiniBox, SizeClass};
+    use std::vec::Vec;
+
+    #[inline(never)]
+    fn moved<T>(bx: MiniBox<T>) -> MiniBox<T> {
+        bx
+    }
+
+    #[test]
+    fn addr_is_deref_addr() {
+        let bx = MiniBox::new(3_u32);
+        assert_eq!(MiniBox::addr(&bx), &*bx as *const u32 as usize);
+
+        let bx = MiniBox::new([3_u32; 8]);
+        assert_eq!(MiniBox::addr(&bx), bx.as_ptr() as usize);
+    }
+
+    #[test]
+    fn inline_moves_with_box() {
+        assert_eq!(MiniBox::<u32>::SIZE_CLASS, SizeClass::Inline);
+
+        let bx = MiniBox::new(7_u32);
+        let addr = MiniBox::addr(&bx);
+        // the inline value is stored in the `MiniBox`
+        assert_eq!(addr, &bx as *const MiniBox<u32> as usize);
+
+        // moving it to the heap moves the value too
+        let boxes = std::vec![bx, MiniBox::new(8)];
+        assert_ne!(MiniBox::addr(&boxes[0]), addr);
+        assert_eq!(
+            MiniBox::addr(&boxes[0]),
+            &boxes[0] as *const MiniBox<u32> as usize
+        );
+        assert_eq!(*boxes[0], 7);
+    }
+
+    #[test]
+    fn boxed_stays_put() {
+        assert_eq!(MiniBox::<[u64; 4]>::SIZE_CLASS, SizeClass::Boxed);
+
+        let bx = MiniBox::new([1_u64; 4]);
+        let addr = MiniBox::addr(&bx);
+        assert_ne!(addr, &bx as *const MiniBox<[u64; 4]> as usize);
+
+        let bx = moved(bx);
+        assert_eq!(MiniBox::addr(&bx), addr);
+
+        let mut vec = Vec::new();
+        vec.push(bx);
+        vec.reserve(64);
+        assert_eq!(MiniBox::addr(&vec[0]), addr);
+    }
+
+    #[test]
+    fn zero_sized_is_dangling() {
+        #[repr(align(64))]
+        struct OverAligned;
+
+        let a = MiniBox::new(OverAligned);
+        let b = moved(MiniBox::new(OverAligned));
+        assert_eq!(MiniBox::addr(&a), MiniBox::addr(&b));
+        assert_ne!(MiniBox::addr(&a), 0);
+        assert_eq!(MiniBox::addr(&a) % 64, 0);
+    }
+
+    #[test]
+    fn ptr_round_trip() {
+        // boxed: the pointer is the heap address, so it survives the round trip
+        let bx = MiniBox::new([2_u64; 4]);
+        let addr = MiniBox::addr(&bx);
+        let ptr = MiniBox::into_ptr(bx);
+        assert_eq!(unsafe { ptr.to_raw() } as usize, addr);
+        let bx = unsafe { MiniBox::from_ptr(ptr) };
+        assert_eq!(MiniBox::addr(&bx), addr);
+
+        // inline: the value is copied along with the storage word, so the
+        // reconstructed `MiniBox` has its own (new) address
+        let bx = MiniBox::new(5_u16);
+        let addr = MiniBox::addr(&bx);
+        let ptr = MiniBox::into_ptr(bx);
+        let bx = unsafe { MiniBox::from_ptr(ptr) };
+        let boxes = std::vec![MiniBox::new(0), bx];
+        assert_ne!(MiniBox::addr(&boxes[1]), addr);
+        assert_eq!(*boxes[1], 5);
+    }
+}
@@ -0,0 +1,33 @@
+// auto traits are gated before `cfg` is evaluated, so they must live in a separate file
+#[cfg(feature = "nightly")]
+mod auto_trait;
+#[cfg(feature = "nightly")]
+pub use auto_trait::NotMiniBox;
+
+/// A marker for types that are not a `MiniBox`
+///
+/// Library authors can bound their generic APIs on `T: NotMiniBox` to reject
+/// `MiniBox<MiniBox<_>>`, which is almost always a mistake.
+///
+/// With the `nightly` feature this is an auto trait that `MiniBox` opts out of,
+/// so the guard is checked at compile time. On stable this is only a marker: it is
+/// implemented for every type (including `MiniBox`), so the bound documents intent
+/// and rejects nothing until the `nightly` feature is enabled.
+#[cfg(not(feature = "nightly"))]
+pub trait NotMiniBox {}
+
+#[cfg(not(feature = "nightly"))]
+impl<T: ?Sized> NotMiniBox for T {}
+
+/// Assert that `T` is not a `MiniBox`
+///
+/// This is checked at compile time by the `NotMiniBox` bound, so it only rejects a
+/// `MiniBox` with the `nightly` feature. On stable it accepts every type, see `NotMiniBox`.
+///
+/// ```rust
+/// # use minibox::assert_not_nested;
+/// assert_not_nested::<u8>();
+/// assert_not_nested::<Box<u8>>();
+/// ```
+#[inline]
+pub const fn assert_not_nested<T: ?Sized + NotMiniBox>() {}
@@ -0,0 +1,22 @@
+use crate::MiniBox;
+
+/// A marker for types that are not a `MiniBox`
+///
+/// Library authors can bound their generic APIs on `T: NotMiniBox` to reject
+/// `MiniBox<MiniBox<_>>`, which is almost always a mistake.
+///
+/// With the `nightly` feature this is an auto trait that `MiniBox` opts out of,
+/// so the guard is checked at compile time (note: like `Send`, any type that contains a
+/// `MiniBox` also opts out). On stable this is only a marker that every type implements.
+///
+/// ```rust,compile_fail
+/// # use minibox::{MiniBox, NotMiniBox};
+/// fn store<T: NotMiniBox>(value: T) -> MiniBox<T> {
+///     MiniBox::new(value)
+/// }
+///
+/// store(MiniBox::new(0_u8));
+/// ```
+pub auto trait NotMiniBox {}
+
+impl<T> !NotMiniBox for MiniBox<T> {}
@@ -0,0 +1,255 @@
+use super::{dangling, MiniBox, MiniPtr, SizeClass};
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+
+const WORD: usize = mem::size_of::<*const ()>();
+
+/// How a `MiniOption<T>` stores its value, and which bit pattern means `None`
+#[derive(Clone, Copy)]
+enum Repr {
+    /// `None` is a null word, `Some` is a dangling (non-null) word
+    Zero,
+    /// the value is stored inline in the first bytes of the word, and the
+    /// last byte of the word is the tag (`0` is `None`, `1` is `Some`)
+    Tagged,
+    /// the value is on the heap (allocated by a `MiniBox`), and `None` is a null pointer
+    Boxed,
+}
+
+impl Repr {
+    const fn new<T>() -> Self {
+        match SizeClass::new::<T>() {
+            SizeClass::Zero => Repr::Zero,
+            SizeClass::Inline if mem::size_of::<T>() < WORD => Repr::Tagged,
+            SizeClass::Inline => panic!(
+                "a pointer-sized `T` leaves no room for the `MiniOption` tag, use `Option<MiniBox<T>>` instead"
+            ),
+            SizeClass::Boxed => Repr::Boxed,
+        }
+    }
+}
+
+/// An optional `MiniBox`, that is always exactly one pointer wide
+///
+/// `MiniBox<T>` has no spare bit patterns (an inline value may use every bit of the word), so
+/// `Option<MiniBox<T>>` is two words. `MiniOption<T>` stores the discriminant in the word itself:
+///
+/// * `SizeClass::Zero`: `None` is a null word, so `MiniOption<T>` never allocates
+/// * `SizeClass::Inline`, if `T` is smaller than a pointer: the value is stored inline and the discriminant
+///   is stored in the last byte of the word, so `MiniOption<T>` never allocates
+/// * `SizeClass::Boxed`: the value is heap allocated (like in a `MiniBox<T>`), and `None` is a null pointer
+///
+/// A `SizeClass::Inline` `T` that is exactly as large as a pointer (like `usize`, `&U` or a `MiniBox<U>`)
+/// leaves no room for the discriminant, so `MiniOption<T>` fails to compile for it. Use `Option<MiniBox<T>>`
+/// for those types instead, which is two words but never allocates
+///
+/// ```rust
+/// # use minibox::MiniOption;
+/// use core::mem::size_of;
+///
+/// assert_eq!(size_of::<MiniOption<[usize; 2]>>(), size_of::<usize>());
+/// assert_eq!(size_of::<MiniOption<u8>>(), size_of::<usize>());
+///
+/// let mut opt = MiniOption::some([1_u64, 2, 3]);
+/// assert_eq!(opt.as_ref(), Some(&[1, 2, 3]));
+/// assert_eq!(opt.take(), Some([1, 2, 3]));
+/// assert!(opt.is_none());
+/// ```
+///
+/// ```rust,compile_fail,E0080
+/// # use minibox::MiniOption;
+/// let opt = MiniOption::some(0_usize);
+/// ```
+pub struct MiniOption<T> {
+    word: MaybeUninit<*const T>,
+    drop: PhantomData<T>,
+}
+
+impl<T> MiniOption<T> {
+    const REPR: Repr = Repr::new::<T>();
+
+    /// A `MiniOption<T>` that doesn't contain a value
+    pub const NONE: Self = Self::none();
+
+    /// Create a new `MiniOption<T>` that doesn't contain a value
+    ///
+    /// This never allocates
+    #[inline]
+    pub const fn none() -> Self {
+        // in every representation, a null word (which has a zero tag byte) is `None`
+        Self {
+            word: MaybeUninit::new(ptr::null()),
+            drop: PhantomData,
+        }
+    }
+
+    /// Create a new `MiniOption<T>` containing `value`
+    ///
+    /// see the type-level documentation for when this allocates
+    #[inline]
+    pub fn some(value: T) -> Self {
+        let mut opt = Self::none();
+
+        match Self::REPR {
+            Repr::Zero => {
+                mem::forget(value);
+                opt.word = MaybeUninit::new(dangling::<T>());
+            }
+            Repr::Tagged => unsafe {
+                let bytes = opt.word.as_mut_ptr().cast::<u8>();
+                bytes.cast::<T>().write(value);
+                bytes.add(WORD - 1).write(1);
+            },
+            Repr::Boxed => unsafe {
+                opt.word = MaybeUninit::new(MiniBox::into_ptr(MiniBox::new(value)).to_raw());
+            },
+        }
+
+        opt
+    }
+
+    /// Returns true if the `MiniOption` contains a value
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        unsafe {
+            match Self::REPR {
+                Repr::Zero | Repr::Boxed => !self.word.assume_init().is_null(),
+                Repr::Tagged => *self.word.as_ptr().cast::<u8>().add(WORD - 1) != 0,
+            }
+        }
+    }
+
+    /// Returns true if the `MiniOption` doesn't contain a value
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /// Get a reference to the contained value, if any
+    #[inline]
+    pub fn as_ref(&self) -> Option<&T> {
+        if self.is_none() {
+            return None;
+        }
+
+        unsafe {
+            Some(match Self::REPR {
+                Repr::Zero => &*dangling::<T>(),
+                Repr::Tagged => &*self.word.as_ptr().cast::<T>(),
+                Repr::Boxed => &*self.word.assume_init(),
+            })
+        }
+    }
+
+    /// Get a mutable reference to the contained value, if any
+    #[inline]
+    pub fn as_mut(&mut self) -> Option<&mut T> {
+        if self.is_none() {
+            return None;
+        }
+
+        unsafe {
+            Some(match Self::REPR {
+                Repr::Zero => &mut *dangling::<T>(),
+                Repr::Tagged => &mut *self.word.as_mut_ptr().cast::<T>(),
+                Repr::Boxed => &mut *(self.word.assume_init() as *mut T),
+            })
+        }
+    }
+
+    /// Take the value out of the `MiniOption`, leaving `None` in its place
+    ///
+    /// The allocation holding the value (if any) is freed
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        if self.is_none() {
+            return None;
+        }
+
+        let value = unsafe {
+            match Self::REPR {
+                Repr::Zero => dangling::<T>().read(),
+                Repr::Tagged => self.word.as_ptr().cast::<T>().read(),
+                Repr::Boxed => MiniBox::into_inner(MiniBox::from_ptr(MiniPtr::from_raw(
+                    self.word.assume_init() as *mut T,
+                ))),
+            }
+        };
+
+        self.word = MaybeUninit::new(ptr::null());
+        Some(value)
+    }
+
+    /// Put `value` into the `MiniOption`, returning the old value (if any)
+    #[inline]
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        let old = self.take();
+        *self = Self::some(value);
+        old
+    }
+
+    /// Convert the `MiniOption<T>` into an `Option<T>`
+    #[inline]
+    pub fn into_option(mut self) -> Option<T> {
+        self.take()
+    }
+}
+
+impl<T> Drop for MiniOption<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+impl<T> Default for MiniOption<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<T> From<Option<T>> for MiniOption<T> {
+    #[inline]
+    fn from(opt: Option<T>) -> Self {
+        match opt {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+
+impl<T> From<MiniOption<T>> for Option<T> {
+    #[inline]
+    fn from(opt: MiniOption<T>) -> Self {
+        opt.into_option()
+    }
+}
+
+impl<T: Clone> Clone for MiniOption<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.as_ref().cloned().into()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MiniOption<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for MiniOption<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<T: Eq> Eq for MiniOption<T> {}
+
+unsafe impl<T: Send> Send for MiniOption<T> {}
+unsafe impl<T: Sync> Sync for MiniOption<T> {}
@@ -0,0 +1,54 @@
+//! Observe when `MiniBox` falls back to heap allocation
+//!
+//! With the `telemetry` feature enabled, the observer set by `set_alloc_observer` is called every time
+//! a `MiniBox` allocates (or reallocates) heap storage for a `SizeClass::Boxed` value. Inline and zero-sized
+//! values never call it. This can be used to collect statistics about how often `MiniBox` actually boxes.
+//!
+//! ```rust
+//! use minibox::telemetry::set_alloc_observer;
+//! use minibox::MiniBox;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static BOXED_BYTES: AtomicUsize = AtomicUsize::new(0);
+//!
+//! set_alloc_observer(|_, layout| {
+//!     BOXED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+//! });
+//!
+//! let _bx = MiniBox::new([0_u64; 4]);
+//! assert_eq!(BOXED_BYTES.load(Ordering::Relaxed), 32);
+//! ```
+
+use crate::SizeClass;
+
+use core::alloc::Layout;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// A function that is notified of heap allocations made by `MiniBox`
+pub type AllocObserver = fn(SizeClass, Layout);
+
+fn noop(_: SizeClass, _: Layout) {}
+
+static OBSERVER: AtomicPtr<()> = AtomicPtr::new(noop as AllocObserver as *mut ());
+
+/// Set the global allocation observer, replacing the previous one
+///
+/// The observer may be called from any thread, and must not allocate with `MiniBox` itself
+#[inline]
+pub fn set_alloc_observer(observer: AllocObserver) {
+    OBSERVER.store(observer as *mut (), Ordering::Release);
+}
+
+/// Reset the global allocation observer to the default, which does nothing
+#[inline]
+pub fn clear_alloc_observer() {
+    set_alloc_observer(noop)
+}
+
+#[inline]
+pub(crate) fn observe(size_class: SizeClass, layout: Layout) {
+    let observer = OBSERVER.load(Ordering::Acquire);
+    // `OBSERVER` only ever holds `AllocObserver`s
+    let observer = unsafe { core::mem::transmute::<*mut (), AllocObserver>(observer) };
+    observer(size_class, layout)
+}
@@ -0,0 +1,126 @@
+//! Utilities for testing the allocation behavior of code that uses `MiniBox`
+//!
+//! This module needs both the `test-util` and `std` features.
+//!
+//! ```rust
+//! use minibox::test_util::CountingAllocator;
+//! use minibox::MiniBox;
+//!
+//! #[global_allocator]
+//! static ALLOC: CountingAllocator = CountingAllocator::new(std::alloc::System);
+//!
+//! ALLOC.assert_no_alloc(|| MiniBox::new(0_u8));
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static ZEROED_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static DEALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static REALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn increment(counter: &'static std::thread::LocalKey<Cell<usize>>) {
+    // the thread local may already be destroyed if this thread is exiting
+    let _ = counter.try_with(|count| count.set(count.get() + 1));
+}
+
+fn get(counter: &'static std::thread::LocalKey<Cell<usize>>) -> usize {
+    counter.with(Cell::get)
+}
+
+/// A `GlobalAlloc` that wraps another allocator, and counts the number of allocations and deallocations
+///
+/// The counts are tracked per thread, so that tests running in parallel don't interfere with each other.
+/// For the counts to be meaningful, this must be installed as the `#[global_allocator]`
+pub struct CountingAllocator<A = System> {
+    inner: A,
+}
+
+impl<A> CountingAllocator<A> {
+    /// Create a new `CountingAllocator` that forwards all allocations to `inner`
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    /// The number of allocations made by the current thread
+    pub fn allocations(&self) -> usize {
+        get(&ALLOCATIONS)
+    }
+
+    /// The number of allocations made by the current thread that requested zeroed memory
+    ///
+    /// note: these are also included in `allocations`
+    pub fn zeroed_allocations(&self) -> usize {
+        get(&ZEROED_ALLOCATIONS)
+    }
+
+    /// The number of deallocations made by the current thread
+    pub fn deallocations(&self) -> usize {
+        get(&DEALLOCATIONS)
+    }
+
+    /// The number of reallocations made by the current thread
+    ///
+    /// note: these are not included in `allocations` or `deallocations`
+    pub fn reallocations(&self) -> usize {
+        get(&REALLOCATIONS)
+    }
+
+    /// Run `f`, and assert that it didn't allocate, deallocate, or reallocate any memory on the current thread
+    ///
+    /// # Panic
+    ///
+    /// if `f` allocates, deallocates, or reallocates any memory
+    #[track_caller]
+    pub fn assert_no_alloc<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        let allocations = self.allocations();
+        let deallocations = self.deallocations();
+        let reallocations = self.reallocations();
+
+        let value = f();
+
+        assert_eq!(self.allocations(), allocations, "unexpected allocation");
+        assert_eq!(
+            self.deallocations(),
+            deallocations,
+            "unexpected deallocation"
+        );
+        assert_eq!(
+            self.reallocations(),
+            reallocations,
+            "unexpected reallocation"
+        );
+
+        value
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        increment(&ALLOCATIONS);
+        self.inner.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        increment(&ALLOCATIONS);
+        increment(&ZEROED_ALLOCATIONS);
+        self.inner.alloc_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        increment(&DEALLOCATIONS);
+        self.inner.dealloc(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        increment(&REALLOCATIONS);
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
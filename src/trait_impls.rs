@@ -1,4 +1,4 @@
-use super::{MiniBox, SizeClass};
+use super::{MiniBox, MiniPtr, SizeClass};
 
 use core::fmt;
 use core::future::Future;
@@ -6,15 +6,40 @@ use core::hash::{Hash, Hasher};
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
+use std::borrow::Cow;
 use std::boxed::Box;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[cfg(feature = "std")]
 use std::error::Error;
 #[cfg(feature = "std")]
 use std::io;
 
+// `default fn` is gated before `cfg` is evaluated, so the specializing impls live in a separate file
+#[cfg(feature = "nightly")]
+mod specialize;
+
 unsafe impl<T: Send> Send for MiniBox<T> {}
 unsafe impl<T: Sync> Sync for MiniBox<T> {}
+// `MiniPtr` is `Copy`, so it's up to the user to not access the same value
+// from multiple threads unless `T: Sync` (which is already `unsafe`)
+unsafe impl<T: Send> Send for super::MiniPtr<T> {}
+unsafe impl<T: Sync> Sync for super::MiniPtr<T> {}
+/// Unlike `Box<T>`, a `MiniBox<T>` is only `Unpin` if `T` is, even if `T` is `SizeClass::Boxed`
+///
+/// Inline values move along with the `MiniBox`, so it can't be unconditionally `Unpin`. A `Boxed`
+/// value does have a stable address, but the size class is a constant, and trait impls can't depend on
+/// constants (not even with specialization), so `Unpin` can't be implemented for only the boxed case.
+/// Use `MiniBox::pin` to pin a boxed value that isn't `Unpin` instead.
+///
+/// ```rust,compile_fail,E0277
+/// # use minibox::MiniBox;
+/// fn assert_unpin<T: Unpin>() {}
+///
+/// // boxed, but still `!Unpin`
+/// assert_unpin::<MiniBox<(core::marker::PhantomPinned, [u64; 4])>>();
+/// ```
 impl<T: core::marker::Unpin> core::marker::Unpin for MiniBox<T> {}
 impl<T: core::marker::Unpin> core::marker::Unpin for super::MiniPtr<T> {}
 
@@ -54,6 +79,15 @@ impl<T> AsMut<MiniBox<T>> for MiniBox<T> {
     }
 }
 
+/// This allows a `MiniBox<PathBuf>` to be passed to functions like `File::open`
+#[cfg(feature = "std")]
+impl AsRef<std::path::Path> for MiniBox<std::path::PathBuf> {
+    #[inline]
+    fn as_ref(&self) -> &std::path::Path {
+        self
+    }
+}
+
 impl<T> std::borrow::Borrow<T> for MiniBox<T> {
     #[inline]
     fn borrow(&self) -> &T {
@@ -68,6 +102,36 @@ impl<T> std::borrow::BorrowMut<T> for MiniBox<T> {
     }
 }
 
+/// arrays hash, compare, and order the same way as slices, so this is consistent with `Borrow<[T; N]>`
+impl<T, const N: usize> std::borrow::Borrow<[T]> for MiniBox<[T; N]> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        &**self
+    }
+}
+
+impl<T, const N: usize> std::borrow::BorrowMut<[T]> for MiniBox<[T; N]> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        &mut **self
+    }
+}
+
+/// `String` hashes, compares, and orders the same way as `str`, so a `MiniBox<String>` key can be looked up with a `&str`
+impl std::borrow::Borrow<str> for MiniBox<std::string::String> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl std::borrow::Borrow<str> for MiniBox<Box<str>> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
 #[cfg(feature = "std")]
 impl<T: Error> Error for MiniBox<T> {
     #[inline]
@@ -80,9 +144,32 @@ impl<T: Error> Error for MiniBox<T> {
     fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
         T::backtrace(self)
     }
+
+    #[inline]
+    #[cfg(feature = "nightly")]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        T::provide(self, request)
+    }
 }
 
 impl<T: Eq> Eq for MiniBox<T> {}
+/// Comparisons require `T: PartialEq`, so a `MiniBox<MaybeUninit<T>>` (which may
+/// contain uninitialized bytes) can't be compared
+///
+/// ```rust,compile_fail,E0369
+/// # use minibox::MiniBox;
+/// # use core::mem::MaybeUninit;
+/// let a = MiniBox::<u32>::new_uninit();
+/// let b = MiniBox::<u32>::new_uninit();
+/// a == b;
+/// ```
+///
+/// ```rust,compile_fail,E0369
+/// # use minibox::MiniBox;
+/// # use core::mem::MaybeUninit;
+/// let a = MiniBox::<[u32; 8]>::new_uninit();
+/// a < a;
+/// ```
 impl<T: PartialEq<U>, U> PartialEq<MiniBox<U>> for MiniBox<T> {
     #[inline]
     fn eq(&self, other: &MiniBox<U>) -> bool {
@@ -90,6 +177,28 @@ impl<T: PartialEq<U>, U> PartialEq<MiniBox<U>> for MiniBox<T> {
     }
 }
 
+macro_rules! shared_eq {
+    ($($shared:ident)*) => {$(
+        #[doc = concat!("Compares the underlying values, to ease migrating between `MiniBox<T>` and `", stringify!($shared), "<T>`")]
+        impl<T: PartialEq<U>, U> PartialEq<$shared<U>> for MiniBox<T> {
+            #[inline]
+            fn eq(&self, other: &$shared<U>) -> bool {
+                T::eq(self, other)
+            }
+        }
+
+        #[doc = concat!("Compares the underlying values, to ease migrating between `MiniBox<T>` and `", stringify!($shared), "<T>`")]
+        impl<T: PartialEq<U>, U> PartialEq<MiniBox<U>> for $shared<T> {
+            #[inline]
+            fn eq(&self, other: &MiniBox<U>) -> bool {
+                T::eq(self, other)
+            }
+        }
+    )*};
+}
+
+shared_eq! { Rc Arc }
+
 impl<T: PartialOrd<U>, U> PartialOrd<MiniBox<U>> for MiniBox<T> {
     #[inline]
     fn partial_cmp(&self, other: &MiniBox<U>) -> Option<core::cmp::Ordering> {
@@ -104,6 +213,25 @@ impl<T: Ord> Ord for MiniBox<T> {
     }
 }
 
+/// This hashes exactly like `T` (i.e. `MiniBox<[u8; N]>` hashes like `[u8; N]`, which in turn hashes like `[u8]`)
+///
+/// Hashing requires `T: Hash`, so a `MiniBox<MaybeUninit<T>>` (which may contain uninitialized
+/// bytes) can't be hashed
+///
+/// ```rust,compile_fail,E0599
+/// # use minibox::MiniBox;
+/// # use std::collections::hash_map::DefaultHasher;
+/// # use std::hash::Hash;
+/// let bx = MiniBox::<u32>::new_uninit();
+/// bx.hash(&mut DefaultHasher::new());
+/// ```
+///
+/// ```rust,compile_fail,E0277
+/// # use minibox::MiniBox;
+/// # use std::collections::HashSet;
+/// let mut set = HashSet::new();
+/// set.insert(MiniBox::<u32>::new_uninit());
+/// ```
 impl<T: Hash> Hash for MiniBox<T> {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -173,6 +301,9 @@ impl<T: Hasher> Hasher for MiniBox<T> {
     }
 }
 
+/// Note that this clones `T` on the stack before moving it into the new `MiniBox`, even for
+/// large arrays, use `MiniBox::clone_array` to avoid that
+#[cfg(not(feature = "nightly"))]
 impl<T: Clone> Clone for MiniBox<T> {
     #[inline]
     fn clone(&self) -> Self {
@@ -192,6 +323,16 @@ impl<T> From<T> for MiniBox<T> {
     }
 }
 
+/// This allows parsing directly into a `MiniBox`, i.e. `"42".parse::<MiniBox<i32>>()`
+impl<T: core::str::FromStr> core::str::FromStr for MiniBox<T> {
+    type Err = T::Err;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, T::Err> {
+        T::from_str(s).map(Self::new)
+    }
+}
+
 impl<T: Clone> From<&T> for MiniBox<T> {
     #[inline]
     fn from(value: &T) -> Self {
@@ -200,6 +341,7 @@ impl<T: Clone> From<&T> for MiniBox<T> {
 }
 
 impl<T> From<Box<T>> for MiniBox<T> {
+    #[inline]
     fn from(value: Box<T>) -> Self {
         match Self::SIZE_CLASS {
             SizeClass::Zero => Self::new_zst(*value),
@@ -212,6 +354,59 @@ impl<T> From<Box<T>> for MiniBox<T> {
     }
 }
 
+/// This is equivalent to `MiniBox::into_ptr`, use `MiniPtr::into_box` to convert back
+impl<T> From<MiniBox<T>> for MiniPtr<T> {
+    #[inline]
+    fn from(value: MiniBox<T>) -> Self {
+        MiniBox::into_ptr(value)
+    }
+}
+
+/// Values that aren't `Unpin` must be pinned with `MiniBox::pin`, because
+/// inline values move along with the `MiniBox`
+impl<T: Unpin> From<MiniBox<T>> for Pin<MiniBox<T>> {
+    #[inline]
+    fn from(value: MiniBox<T>) -> Self {
+        Pin::new(value)
+    }
+}
+
+/// This moves the value into a new allocation, see `MiniBox::into_arc`
+impl<T> From<MiniBox<T>> for Arc<T> {
+    #[inline]
+    fn from(value: MiniBox<T>) -> Self {
+        MiniBox::into_arc(value)
+    }
+}
+
+/// This moves the value into a new allocation, see `MiniBox::into_rc`
+impl<T> From<MiniBox<T>> for Rc<T> {
+    #[inline]
+    fn from(value: MiniBox<T>) -> Self {
+        MiniBox::into_rc(value)
+    }
+}
+
+impl<'a, T: Clone> From<MiniBox<T>> for Cow<'a, T> {
+    #[inline]
+    fn from(value: MiniBox<T>) -> Self {
+        Cow::Owned(MiniBox::into_inner(value))
+    }
+}
+
+/// This only shows `T` and its size class, i.e. `MiniPtr::<u32>(Inline)`, and never reads the storage word,
+/// because it may contain uninitialized bytes. Use `MiniPtr::debug_word` to also show the storage word
+impl<T> fmt::Debug for MiniPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MiniPtr::<{}>({:?})",
+            core::any::type_name::<T>(),
+            Self::SIZE_CLASS
+        )
+    }
+}
+
 impl<T: fmt::Debug> fmt::Debug for MiniBox<T> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -226,8 +421,36 @@ impl<T: fmt::Display> fmt::Display for MiniBox<T> {
     }
 }
 
+impl<T: fmt::Write> fmt::Write for MiniBox<T> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        T::write_str(self, s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        T::write_char(self, c)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        T::write_fmt(self, args)
+    }
+}
+
+/// This allows `MiniBox<String>` (and other collections) to be extended directly,
+/// e.g. with an iterator of `char`s or `&str`s
+impl<A, T: Extend<A>> Extend<A> for MiniBox<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = A>>(&mut self, iter: I) {
+        T::extend(self, iter)
+    }
+}
+
 impl<I: ExactSizeIterator> ExactSizeIterator for MiniBox<I> {}
 impl<I: core::iter::FusedIterator> core::iter::FusedIterator for MiniBox<I> {}
+#[cfg(feature = "nightly")]
+unsafe impl<I: core::iter::TrustedLen> core::iter::TrustedLen for MiniBox<I> {}
 impl<I: Iterator> Iterator for MiniBox<I> {
     type Item = I::Item;
 
@@ -250,6 +473,46 @@ impl<I: Iterator> Iterator for MiniBox<I> {
     fn last(self) -> Option<I::Item> {
         I::last(Self::into_inner(self))
     }
+
+    #[inline]
+    fn count(self) -> usize {
+        I::count(Self::into_inner(self))
+    }
+
+    #[inline]
+    fn fold<B, F: FnMut(B, I::Item) -> B>(self, init: B, f: F) -> B {
+        I::fold(Self::into_inner(self), init, f)
+    }
+
+    #[inline]
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, f: F) -> R
+    where
+        F: FnMut(B, I::Item) -> R,
+        R: core::ops::Try<Output = B>,
+    {
+        I::try_fold(self, init, f)
+    }
+
+    #[inline]
+    fn for_each<F: FnMut(I::Item)>(self, f: F) {
+        I::for_each(Self::into_inner(self), f)
+    }
+
+    #[inline]
+    fn sum<S: core::iter::Sum<I::Item>>(self) -> S {
+        I::sum(Self::into_inner(self))
+    }
+
+    #[inline]
+    fn product<P: core::iter::Product<I::Item>>(self) -> P {
+        I::product(Self::into_inner(self))
+    }
+
+    #[inline]
+    fn collect<B: core::iter::FromIterator<I::Item>>(self) -> B {
+        I::collect(Self::into_inner(self))
+    }
 }
 
 impl<I: DoubleEndedIterator> DoubleEndedIterator for MiniBox<I> {
@@ -262,6 +525,21 @@ impl<I: DoubleEndedIterator> DoubleEndedIterator for MiniBox<I> {
     fn nth_back(&mut self, n: usize) -> Option<I::Item> {
         I::nth_back(self, n)
     }
+
+    #[inline]
+    fn rfold<B, F: FnMut(B, I::Item) -> B>(self, init: B, f: F) -> B {
+        I::rfold(Self::into_inner(self), init, f)
+    }
+
+    #[inline]
+    #[cfg(feature = "nightly")]
+    fn try_rfold<B, F, R>(&mut self, init: B, f: F) -> R
+    where
+        F: FnMut(B, I::Item) -> R,
+        R: core::ops::Try<Output = B>,
+    {
+        I::try_rfold(self, init, f)
+    }
 }
 
 impl<T: Future> Future for MiniBox<T> {
@@ -372,6 +650,54 @@ impl<T: io::BufRead> io::BufRead for MiniBox<T> {
     }
 }
 
+#[cfg(all(unix, feature = "std"))]
+impl<T: std::os::unix::io::AsRawFd> std::os::unix::io::AsRawFd for MiniBox<T> {
+    #[inline]
+    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
+        T::as_raw_fd(self)
+    }
+}
+
+#[cfg(all(unix, feature = "std"))]
+impl<T: std::os::unix::io::AsFd> std::os::unix::io::AsFd for MiniBox<T> {
+    #[inline]
+    fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> {
+        T::as_fd(self)
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+impl<T: std::os::windows::io::AsRawHandle> std::os::windows::io::AsRawHandle for MiniBox<T> {
+    #[inline]
+    fn as_raw_handle(&self) -> std::os::windows::io::RawHandle {
+        T::as_raw_handle(self)
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+impl<T: std::os::windows::io::AsHandle> std::os::windows::io::AsHandle for MiniBox<T> {
+    #[inline]
+    fn as_handle(&self) -> std::os::windows::io::BorrowedHandle<'_> {
+        T::as_handle(self)
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+impl<T: std::os::windows::io::AsRawSocket> std::os::windows::io::AsRawSocket for MiniBox<T> {
+    #[inline]
+    fn as_raw_socket(&self) -> std::os::windows::io::RawSocket {
+        T::as_raw_socket(self)
+    }
+}
+
+#[cfg(all(windows, feature = "std"))]
+impl<T: std::os::windows::io::AsSocket> std::os::windows::io::AsSocket for MiniBox<T> {
+    #[inline]
+    fn as_socket(&self) -> std::os::windows::io::BorrowedSocket<'_> {
+        T::as_socket(self)
+    }
+}
+
 #[cfg(feature = "nightly")]
 impl<T: FnOnce<A>, A> FnOnce<A> for MiniBox<T> {
     type Output = T::Output;
@@ -0,0 +1,23 @@
+use crate::MiniBox;
+
+/// Note that this clones `T` on the stack before moving it into the new `MiniBox`, except for
+/// arrays, which are cloned element by element (see `MiniBox::clone_array`)
+impl<T: Clone> Clone for MiniBox<T> {
+    #[inline]
+    default fn clone(&self) -> Self {
+        Self::new_uninit().write(T::clone(self))
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &Self) {
+        T::clone_from(self, other)
+    }
+}
+
+/// clone arrays element by element, directly into the new `MiniBox`, see `MiniBox::clone_array`
+impl<T: Clone, const N: usize> Clone for MiniBox<[T; N]> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::clone_array(self)
+    }
+}
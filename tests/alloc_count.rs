@@ -0,0 +1,410 @@
+// `CountingAllocator` needs `std`
+#![cfg(feature = "std")]
+
+use minibox::test_util::CountingAllocator;
+use minibox::MiniBox;
+use std::alloc::System;
+
+#[global_allocator]
+static A: CountingAllocator = CountingAllocator::new(System);
+
+#[test]
+fn inline_no_alloc() {
+    A.assert_no_alloc(|| {
+        #[repr(align(64))]
+        struct OverAlignedZeroSized;
+
+        drop(MiniBox::new(0_u8));
+        drop(MiniBox::new([10_u8; 4]));
+        drop(MiniBox::new(()));
+        drop(MiniBox::new(OverAlignedZeroSized));
+    });
+}
+
+#[test]
+fn boxed_single_alloc() {
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+
+    let bx = MiniBox::new([0_u8; 64]);
+    assert_eq!(A.allocations() - allocations, 1);
+    assert_eq!(A.deallocations() - deallocations, 0);
+
+    drop(bx);
+    assert_eq!(A.allocations() - allocations, 1);
+    assert_eq!(A.deallocations() - deallocations, 1);
+}
+
+#[test]
+#[should_panic]
+fn assert_no_alloc_fails() {
+    A.assert_no_alloc(|| MiniBox::new([0_u8; 64]));
+}
+
+#[test]
+fn clone_from_reuses_allocation() {
+    let src = MiniBox::new(vec![1_u8, 2, 3, 4]);
+    let mut dst = MiniBox::new(Vec::with_capacity(16));
+    dst.push(10);
+
+    let header = &*dst as *const Vec<u8>;
+    let buffer = dst.as_ptr();
+
+    A.assert_no_alloc(|| dst.clone_from(&src));
+
+    assert_eq!(*dst, [1, 2, 3, 4]);
+    assert_eq!(&*dst as *const Vec<u8>, header);
+    assert_eq!(dst.as_ptr(), buffer);
+}
+
+#[test]
+fn map_realloc() {
+    let bx = MiniBox::new([1_u8; 64]);
+
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+    let reallocations = A.reallocations();
+
+    let bx = MiniBox::map_realloc(bx, |old| {
+        let mut new = [2_u8; 128];
+        new[..64].copy_from_slice(&old);
+        new
+    });
+
+    assert_eq!(A.allocations(), allocations);
+    assert_eq!(A.deallocations(), deallocations);
+    assert_eq!(A.reallocations() - reallocations, 1);
+    assert_eq!(bx[..64], [1; 64]);
+    assert_eq!(bx[64..], [2; 64]);
+}
+
+#[test]
+fn box_in_minibox() {
+    let allocations = A.allocations();
+    let bx = MiniBox::new(Box::new(0_u8));
+    assert_eq!(A.allocations() - allocations, 1);
+    assert_eq!(**bx, 0);
+
+    let deallocations = A.deallocations();
+    drop(bx);
+    assert_eq!(A.deallocations() - deallocations, 1);
+
+    let inner = Box::new([1_u64; 8]);
+    let addr = &*inner as *const [u64; 8];
+    let bx = A.assert_no_alloc(|| MiniBox::new(inner));
+    assert_eq!(&**bx as *const [u64; 8], addr);
+}
+
+#[test]
+fn emplace() {
+    let allocations = A.allocations();
+
+    let bx = unsafe {
+        MiniBox::<[u32; 256]>::emplace(|slot| {
+            let ptr = slot.as_mut_ptr().cast::<u32>();
+            for i in 0..256 {
+                ptr.add(i).write(i as u32 * 3);
+            }
+        })
+    };
+
+    assert_eq!(A.allocations() - allocations, 1);
+    assert!(bx.iter().enumerate().all(|(i, &x)| x == i as u32 * 3));
+
+    let bx = A.assert_no_alloc(|| unsafe {
+        MiniBox::<u32>::emplace(|slot| {
+            slot.write(10);
+        })
+    });
+    assert_eq!(*bx, 10);
+}
+
+#[test]
+fn try_map_frees_on_error() {
+    let bx = MiniBox::new([1_u64; 8]);
+
+    let deallocations = A.deallocations();
+    let result = MiniBox::try_map(bx, |_| Err::<u8, _>("failed"));
+    assert_eq!(result.err(), Some("failed"));
+    assert_eq!(A.deallocations() - deallocations, 1);
+}
+
+#[test]
+fn zeroed_default() {
+    let allocations = A.allocations();
+    let zeroed_allocations = A.zeroed_allocations();
+
+    let bx = MiniBox::<[[u64; 32]; 32]>::zeroed_default();
+    assert_eq!(A.allocations() - allocations, 1);
+    assert_eq!(A.zeroed_allocations() - zeroed_allocations, 1);
+    assert!(bx.iter().flatten().all(|&x| x == 0));
+
+    let bx = A.assert_no_alloc(MiniBox::<(u16, u32)>::zeroed_default);
+    assert_eq!(*bx, (0, 0));
+}
+
+#[test]
+#[cfg(feature = "nightly")]
+fn new_zeroed_default() {
+    let zeroed_allocations = A.zeroed_allocations();
+
+    let bx = MiniBox::new(<[[u64; 32]; 32]>::default());
+    assert_eq!(A.zeroed_allocations() - zeroed_allocations, 1);
+    assert!(bx.iter().flatten().all(|&x| x == 0));
+
+    let bx = MiniBox::from([[0_u64; 32]; 32]);
+    assert_eq!(A.zeroed_allocations() - zeroed_allocations, 2);
+    assert!(bx.iter().flatten().all(|&x| x == 0));
+
+    // non-zero values are written as usual
+    let bx = MiniBox::new([[1_u64; 32]; 32]);
+    assert_eq!(A.zeroed_allocations() - zeroed_allocations, 2);
+    assert!(bx.iter().flatten().all(|&x| x == 1));
+}
+
+#[test]
+fn into_inner_recycle() {
+    let bx = MiniBox::new(Box::new(10_u8));
+    let (value, storage) = A.assert_no_alloc(|| MiniBox::into_inner_recycle(bx));
+    assert!(storage.is_none());
+    assert_eq!(*value, 10);
+
+    let bx = MiniBox::new([1_u32; 16]);
+    let (value, storage) = A.assert_no_alloc(|| MiniBox::into_inner_recycle(bx));
+    assert_eq!(value, [1; 16]);
+
+    let storage = storage.unwrap();
+    let address = storage.as_ptr() as usize;
+    let bx = A.assert_no_alloc(|| storage.write([2; 16]));
+    assert_eq!(*bx, [2; 16]);
+    assert_eq!(&*bx as *const [u32; 16] as usize, address);
+
+    // dropping unused storage frees the allocation
+    let (_, storage) = MiniBox::into_inner_recycle(bx);
+    let deallocations = A.deallocations();
+    drop(storage);
+    assert_eq!(A.deallocations() - deallocations, 1);
+}
+
+#[test]
+fn intrusive_list() {
+    use minibox::MiniBoxHandle;
+
+    struct Node {
+        value: u64,
+        next: Option<MiniBoxHandle<Node>>,
+    }
+
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+
+    // build 3 -> 2 -> 1 -> 0 by pushing to the front
+    let mut head = None;
+    for value in 0..4 {
+        let node = MiniBox::new(Node { value, next: head });
+        head = Some(MiniBoxHandle::from_box(node));
+    }
+    assert_eq!(A.allocations() - allocations, 4);
+
+    // mutate every node through its handle
+    let mut cursor = head;
+    while let Some(node) = cursor {
+        let node = unsafe { node.as_mut() };
+        node.value *= 10;
+        cursor = node.next;
+    }
+
+    let mut values = Vec::new();
+    let mut cursor = head;
+    while let Some(node) = cursor {
+        let node = unsafe { node.as_ref() };
+        values.push(node.value);
+        cursor = node.next;
+    }
+    assert_eq!(values, [30, 20, 10, 0]);
+
+    // tear down
+    let mut cursor = head;
+    while let Some(node) = cursor {
+        let node = unsafe { node.into_box() };
+        cursor = node.next;
+    }
+    assert_eq!(A.deallocations() - deallocations, 4);
+}
+
+#[test]
+#[should_panic]
+fn handle_inline() {
+    minibox::MiniBoxHandle::from_box(MiniBox::new(1_u8));
+}
+
+#[test]
+fn replace_with() {
+    let mut bx = MiniBox::new([1_u64; 8]);
+    let addr = &*bx as *const [u64; 8];
+
+    A.assert_no_alloc(|| {
+        MiniBox::replace_with(&mut bx, |mut array| {
+            array[0] = 2;
+            array
+        })
+    });
+
+    assert_eq!(bx[0], 2);
+    assert_eq!(&*bx as *const [u64; 8], addr);
+
+    let mut bx = MiniBox::new(String::from("hello"));
+    A.assert_no_alloc(|| MiniBox::replace_with(&mut bx, |s| s));
+    assert_eq!(*bx, "hello");
+
+    let mut bx = MiniBox::new(3_u8);
+    A.assert_no_alloc(|| MiniBox::replace_with(&mut bx, |x| x + 1));
+    assert_eq!(*bx, 4);
+}
+
+#[test]
+fn new_zeroed() {
+    fn check<const N: usize>() {
+        // leave some dirty memory of the same size behind for the allocator to hand back
+        drop(MiniBox::new([0xAA_u8; N]));
+
+        let allocations = A.allocations();
+        let zeroed_allocations = A.zeroed_allocations();
+
+        let bx = unsafe { MiniBox::<[u8; N]>::new_zeroed().assume_init() };
+        assert!(bx.iter().all(|&x| x == 0));
+        assert_eq!(A.allocations() - allocations, 1);
+        assert_eq!(A.zeroed_allocations() - zeroed_allocations, 1);
+
+        let bx = MiniBox::<[u8; N]>::try_new_zeroed().unwrap();
+        assert!(unsafe { bx.assume_init() }.iter().all(|&x| x == 0));
+    }
+
+    check::<16>();
+    check::<64>();
+    check::<65>();
+    check::<4096>();
+
+    let bx = A.assert_no_alloc(|| unsafe { MiniBox::<[u16; 2]>::new_zeroed().assume_init() });
+    assert_eq!(*bx, [0, 0]);
+}
+
+#[test]
+fn drop_uninit() {
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+
+    drop(MiniBox::<[String; 8]>::new_uninit());
+    drop(MiniBox::<[String; 8]>::new_zeroed());
+
+    assert_eq!(A.allocations() - allocations, 2);
+    assert_eq!(A.deallocations() - deallocations, 2);
+
+    A.assert_no_alloc(|| drop(MiniBox::<String>::new_uninit_array::<0>()));
+}
+
+#[test]
+fn into_vec() {
+    let bx = MiniBox::new([7_u64; 64]);
+    let vec = A.assert_no_alloc(|| MiniBox::into_vec(bx));
+    assert_eq!(vec, [7; 64]);
+}
+
+#[test]
+fn mini_option() {
+    use minibox::MiniOption;
+
+    A.assert_no_alloc(|| drop(MiniOption::some([1_u8; 3])));
+    A.assert_no_alloc(|| drop(MiniOption::some(())));
+    A.assert_no_alloc(|| drop(MiniOption::<[u64; 4]>::none()));
+
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+    let opt = MiniOption::some([1_u64; 4]);
+    assert_eq!(A.allocations() - allocations, 1);
+    drop(opt);
+    assert_eq!(A.deallocations() - deallocations, 1);
+}
+
+#[test]
+fn flatten() {
+    // the outer box stores the inner one inline, so only the inner value may be allocated
+    let bx = MiniBox::new(MiniBox::new([3_u64; 8]));
+    let bx = A.assert_no_alloc(|| MiniBox::flatten(bx));
+    assert_eq!(*bx, [3; 8]);
+
+    let deallocations = A.deallocations();
+    drop(bx);
+    assert_eq!(A.deallocations() - deallocations, 1);
+
+    let allocations = A.allocations();
+    let bx = MiniBox::new(MiniBox::new(MiniBox::new(7_u8)));
+    let bx = MiniBox::flatten(MiniBox::flatten(bx));
+    assert_eq!(A.allocations() - allocations, 0);
+    assert_eq!(*bx, 7);
+
+    let bx = MiniBox::new(MiniBox::new(String::from("hello")));
+    let bx = A.assert_no_alloc(|| MiniBox::flatten(bx));
+    let deallocations = A.deallocations();
+    drop(bx);
+    // the `String`'s buffer and the `MiniBox`'s allocation
+    assert_eq!(A.deallocations() - deallocations, 2);
+}
+
+#[test]
+fn mini_cow() {
+    use minibox::MiniCow;
+
+    let small = 10_u32;
+    let large = [1_u64; 8];
+
+    // borrowing never allocates
+    let mut small_cow = A.assert_no_alloc(|| MiniCow::from_borrowed(&small));
+    let mut large_cow = A.assert_no_alloc(|| MiniCow::from_borrowed(&large));
+    assert_eq!(*small_cow, 10);
+    assert_eq!(large_cow[0], 1);
+
+    // promoting a small value stores it inline
+    A.assert_no_alloc(|| *small_cow.to_mut() += 1);
+    assert_eq!(*small_cow, 11);
+    assert!(small_cow.is_owned());
+
+    // promoting a large value allocates once, and only the first time
+    let allocations = A.allocations();
+    large_cow.to_mut()[0] = 2;
+    large_cow.to_mut()[1] = 3;
+    assert_eq!(A.allocations() - allocations, 1);
+    assert_eq!(large_cow[..3], [2, 3, 1]);
+    assert_eq!(large, [1; 8]);
+
+    let owned = A.assert_no_alloc(|| large_cow.into_owned());
+    assert_eq!(owned[0], 2);
+}
+
+#[test]
+fn from_fn_panic_frees() {
+    use std::panic::{catch_unwind, resume_unwind};
+
+    let allocations = A.allocations();
+    let deallocations = A.deallocations();
+
+    // `resume_unwind` skips the panic hook, so nothing is printed (and kept in the output capture)
+    let result = catch_unwind(|| {
+        MiniBox::<[Box<u32>; 16]>::from_fn(|i| {
+            if i == 4 {
+                resume_unwind(Box::new(()));
+            }
+
+            Box::new(i as u32)
+        })
+    });
+    assert!(result.is_err());
+    drop(result);
+
+    // the array and the first four elements
+    assert!(A.allocations() - allocations >= 5);
+    assert_eq!(
+        A.allocations() - allocations,
+        A.deallocations() - deallocations
+    );
+}
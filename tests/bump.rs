@@ -0,0 +1,162 @@
+#![cfg(feature = "bumpalo")]
+#![no_std]
+
+extern crate alloc;
+
+use bumpalo::Bump;
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use minibox::{BumpMiniBox, MiniBox};
+use static_alloc::Bump as StaticBump;
+
+pub struct PanicOnAlloc(StaticBump<[u8; 1 << 20]>);
+
+static FLAG: AtomicBool = AtomicBool::new(false);
+
+use alloc::alloc::{GlobalAlloc, Layout};
+unsafe impl GlobalAlloc for PanicOnAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if FLAG.load(SeqCst) {
+            panic!("tried to allocate in a noalloc test")
+        }
+
+        GlobalAlloc::alloc(&self.0, layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if FLAG.load(SeqCst) {
+            panic!("tried to deallocate in a noalloc test")
+        }
+
+        GlobalAlloc::dealloc(&self.0, ptr, layout)
+    }
+}
+
+#[global_allocator]
+static A: PanicOnAlloc = PanicOnAlloc(StaticBump::uninit());
+
+fn with<F: FnOnce()>(f: F) {
+    struct OnDrop;
+
+    impl Drop for OnDrop {
+        fn drop(&mut self) {
+            FLAG.store(false, SeqCst);
+        }
+    }
+
+    assert!(!FLAG.swap(true, SeqCst));
+    let _on_drop = OnDrop;
+
+    f()
+}
+
+struct DropCounter<'a> {
+    counter: &'a Cell<u32>,
+    _value: [u64; 4],
+}
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.counter.set(self.counter.get() + 1);
+    }
+}
+
+#[test]
+fn values() {
+    let bump = Bump::new();
+
+    let boxes = (0..64_u64)
+        .map(|i| MiniBox::new_in_bump([i; 4], &bump))
+        .collect::<alloc::vec::Vec<_>>();
+
+    for (i, bx) in boxes.iter().enumerate() {
+        assert_eq!(**bx, [i as u64; 4]);
+    }
+
+    let inline = BumpMiniBox::new(3_u8, &bump);
+    assert_eq!(*inline, 3);
+    assert_eq!(BumpMiniBox::into_inner(inline), 3);
+
+    let mut boxed = BumpMiniBox::new([1_u64; 4], &bump);
+    boxed[2] = 10;
+    assert_eq!(BumpMiniBox::into_inner(boxed), [1, 1, 10, 1]);
+}
+
+#[test]
+fn drop_without_dealloc() {
+    let bump = Bump::new();
+    let counter = Cell::new(0);
+
+    let boxes = (0..64)
+        .map(|_| {
+            MiniBox::new_in_bump(
+                DropCounter {
+                    counter: &counter,
+                    _value: [0; 4],
+                },
+                &bump,
+            )
+        })
+        .collect::<alloc::vec::Vec<_>>();
+
+    let mut boxes = core::mem::ManuallyDrop::new(boxes);
+
+    with(|| {
+        for bx in boxes.drain(..) {
+            drop(bx);
+        }
+    });
+
+    assert_eq!(counter.get(), 64);
+    drop(core::mem::ManuallyDrop::into_inner(boxes));
+}
+
+#[test]
+fn emplace_in() {
+    let bump = Bump::new();
+
+    let bx = unsafe {
+        BumpMiniBox::<[u64; 64]>::emplace_in(&bump, |slot| {
+            let ptr = slot.as_mut_ptr().cast::<u64>();
+            for i in 0..64 {
+                ptr.add(i).write(i as u64);
+            }
+        })
+    };
+    assert!(bx.iter().enumerate().all(|(i, &x)| x == i as u64));
+
+    let bx = unsafe {
+        BumpMiniBox::<u16>::emplace_in(&bump, |slot| {
+            slot.write(7);
+        })
+    };
+    assert_eq!(*bx, 7);
+}
+
+#[test]
+fn raw_round_trip() {
+    let bump = Bump::new();
+    let counter = Cell::new(0);
+
+    let bx = BumpMiniBox::new(
+        DropCounter {
+            counter: &counter,
+            _value: [0; 4],
+        },
+        &bump,
+    );
+    let addr = &*bx as *const DropCounter;
+
+    let ptr = BumpMiniBox::into_raw(bx);
+    assert_eq!(counter.get(), 0);
+
+    let bx = unsafe { BumpMiniBox::from_raw_in(ptr, &bump) };
+    assert_eq!(&*bx as *const DropCounter, addr);
+
+    with(|| drop(bx));
+    assert_eq!(counter.get(), 1);
+
+    let ptr = BumpMiniBox::into_raw(BumpMiniBox::new(5_u32, &bump));
+    let bx = unsafe { BumpMiniBox::from_raw_in(ptr, &bump) };
+    assert_eq!(BumpMiniBox::into_inner(bx), 5);
+}
@@ -0,0 +1,73 @@
+use minibox::MiniBox;
+use std::alloc::{GlobalAlloc, Layout, System};
+
+/// An allocator that fails to allocate anything larger than 1 MiB
+pub struct FailLargeAlloc;
+
+const LIMIT: usize = 1 << 20;
+
+unsafe impl GlobalAlloc for FailLargeAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > LIMIT {
+            return core::ptr::null_mut();
+        }
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static A: FailLargeAlloc = FailLargeAlloc;
+
+#[test]
+fn try_new_with_capacity() {
+    let bx = MiniBox::<Vec<u8>>::try_new_with_capacity(1024).unwrap();
+    assert!(bx.capacity() >= 1024);
+    assert!(bx.is_empty());
+
+    assert!(MiniBox::<Vec<u8>>::try_new_with_capacity(LIMIT + 1).is_err());
+    assert!(MiniBox::<Vec<u64>>::try_new_with_capacity(usize::MAX).is_err());
+}
+
+#[test]
+fn try_new() {
+    let bx = MiniBox::try_new([7_u64; 4]).unwrap();
+    assert_eq!(*bx, [7; 4]);
+
+    let bx = MiniBox::try_new(1_u8).unwrap();
+    assert_eq!(*bx, 1);
+
+    assert!(MiniBox::<[u8; LIMIT + 1]>::try_new_uninit().is_err());
+}
+
+#[test]
+fn try_new_zeroed() {
+    let bx = MiniBox::<[u64; 16]>::try_new_zeroed().unwrap();
+    assert_eq!(unsafe { bx.assume_init() }[..], [0; 16]);
+
+    let bx = MiniBox::<u32>::try_new_zeroed().unwrap();
+    assert_eq!(unsafe { *bx.assume_init() }, 0);
+
+    assert_eq!(
+        MiniBox::<[u8; LIMIT + 1]>::try_new_zeroed().err(),
+        Some(minibox::AllocError)
+    );
+}
+
+#[test]
+fn try_zeroed() {
+    let bx = MiniBox::<[[u8; 1024]; 16]>::try_zeroed().unwrap();
+    assert!(bx.iter().flatten().all(|&x| x == 0));
+
+    let bx = MiniBox::<(u16, u32)>::try_zeroed().unwrap();
+    assert_eq!(*bx, (0, 0));
+
+    assert_eq!(
+        MiniBox::<[[u8; 2048]; 1024]>::try_zeroed().err(),
+        Some(minibox::AllocError)
+    );
+}
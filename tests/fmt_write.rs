@@ -0,0 +1,57 @@
+// This crate is `no_std`, and `minibox` is built without `std` under
+// `cargo test --no-default-features`.
+#![no_std]
+
+use core::fmt::{self, Write};
+use minibox::MiniBox;
+use static_alloc::Bump;
+
+#[global_allocator]
+static A: Bump<[u8; 1 << 20]> = Bump::uninit();
+
+struct Buffer {
+    data: [u8; 64],
+    len: usize,
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let data = self
+            .data
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(fmt::Error)?;
+        data.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+#[test]
+fn write_boxed() {
+    let mut bx = MiniBox::new(Buffer {
+        data: [0; 64],
+        len: 0,
+    });
+
+    write!(bx, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+    bx.write_char('!').unwrap();
+    assert_eq!(&bx.data[..bx.len], b"1 + 2 = 3!");
+
+    assert!(write!(bx, "{:100}", 0).is_err());
+}
+
+#[test]
+fn write_inline() {
+    let mut bx = MiniBox::new(Counter(0));
+    write!(bx, "{:>10}", "abc").unwrap();
+    assert_eq!(bx.0, 10);
+}
@@ -6,7 +6,9 @@ use core::sync::atomic::{AtomicBool, Ordering::SeqCst};
 use minibox::MiniBox;
 use static_alloc::Bump;
 
-pub struct PanicOnAlloc(Bump<[u8; 1 << 16]>);
+// the test harness (threads, output capture) also allocates from the global
+// allocator, 64KiB isn't enough for it on current toolchains
+pub struct PanicOnAlloc(Bump<[u8; 1 << 20]>);
 
 static FLAG: AtomicBool = AtomicBool::new(false);
 
@@ -0,0 +1,55 @@
+//! `MiniBox::replace_with` aborts the process if its function panics, so the panicking case
+//! is run in a child process (this same test binary, re-executed with `CHILD_ENV` set)
+
+// Miri can't spawn processes
+#![cfg(not(miri))]
+
+use minibox::MiniBox;
+
+use std::env;
+use std::process::Command;
+
+const CHILD_ENV: &str = "MINIBOX_REPLACE_WITH_CHILD";
+
+fn run_child(test: &str, case: &str) -> std::process::Output {
+    Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, case)
+        .output()
+        .unwrap()
+}
+
+fn panic_in_child<T>(mut bx: MiniBox<T>) {
+    MiniBox::replace_with(&mut bx, |_| panic!("replace_with panic"));
+    unreachable!()
+}
+
+#[test]
+fn abort_on_panic() {
+    match env::var(CHILD_ENV).as_deref() {
+        Ok("inline") => panic_in_child(MiniBox::new(1_u8)),
+        Ok("boxed") => panic_in_child(MiniBox::new([1_u64; 8])),
+        Ok(case) => panic!("unknown case {}", case),
+        Err(_) => {
+            for case in ["inline", "boxed"] {
+                let output = run_child("abort_on_panic", case);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+
+                assert!(!output.status.success(), "{}: {}", case, stderr);
+                assert!(
+                    stderr.contains("replace_with panic"),
+                    "{}: {}",
+                    case,
+                    stderr
+                );
+                assert!(stderr.contains("aborting"), "{}: {}", case, stderr);
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::process::ExitStatusExt;
+                    assert_eq!(output.status.signal(), Some(6), "{}: {}", case, stderr);
+                }
+            }
+        }
+    }
+}
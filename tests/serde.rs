@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use minibox::{MiniBox, SizeClass};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+// `MiniBox` passes the serializer through untouched, so types that check
+// `is_human_readable` must produce exactly the same output as when unwrapped
+
+#[test]
+fn ipv4_inline() {
+    assert_eq!(MiniBox::<Ipv4Addr>::SIZE_CLASS, SizeClass::Inline);
+
+    let addr = Ipv4Addr::new(127, 0, 0, 1);
+    let bx = MiniBox::new(addr);
+
+    // human readable, a string
+    let json = serde_json::to_string(&bx).unwrap();
+    assert_eq!(json, "\"127.0.0.1\"");
+    assert_eq!(json, serde_json::to_string(&addr).unwrap());
+    assert_eq!(
+        serde_json::from_str::<MiniBox<Ipv4Addr>>(&json).unwrap(),
+        bx
+    );
+
+    // binary, the raw octets
+    let bytes = bincode::serialize(&bx).unwrap();
+    assert_eq!(bytes, [127, 0, 0, 1]);
+    assert_eq!(bytes, bincode::serialize(&addr).unwrap());
+    assert_eq!(
+        bincode::deserialize::<MiniBox<Ipv4Addr>>(&bytes).unwrap(),
+        bx
+    );
+}
+
+#[test]
+fn ip_addr_boxed() {
+    assert_eq!(MiniBox::<IpAddr>::SIZE_CLASS, SizeClass::Boxed);
+
+    let addr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+    let bx = MiniBox::new(addr);
+
+    let json = serde_json::to_string(&bx).unwrap();
+    assert_eq!(json, "\"::1\"");
+    assert_eq!(json, serde_json::to_string(&addr).unwrap());
+    assert_eq!(serde_json::from_str::<MiniBox<IpAddr>>(&json).unwrap(), bx);
+
+    let bytes = bincode::serialize(&bx).unwrap();
+    assert_eq!(bytes, bincode::serialize(&addr).unwrap());
+    assert!(bytes.len() > "::1".len());
+    assert_eq!(bincode::deserialize::<MiniBox<IpAddr>>(&bytes).unwrap(), bx);
+}
+
+// `MiniBox` forwards to `T` without wrapping it in a newtype, so derived
+// `transparent` and `flatten` attributes see through it
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Id(MiniBox<u32>);
+
+#[test]
+fn transparent_newtype() {
+    let id = Id(MiniBox::new(42));
+
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!(json, "42");
+    assert_eq!(json, serde_json::to_string(&42_u32).unwrap());
+    assert_eq!(serde_json::from_str::<Id>(&json).unwrap(), id);
+
+    let bytes = bincode::serialize(&id).unwrap();
+    assert_eq!(bytes, bincode::serialize(&42_u32).unwrap());
+    assert_eq!(bincode::deserialize::<Id>(&bytes).unwrap(), id);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Entity {
+    name: String,
+    #[serde(flatten)]
+    position: MiniBox<Position>,
+}
+
+#[test]
+fn flatten_field() {
+    assert_eq!(MiniBox::<Position>::SIZE_CLASS, SizeClass::Inline);
+
+    let entity = Entity {
+        name: "player".into(),
+        position: MiniBox::new(Position { x: 1, y: -2 }),
+    };
+
+    let json = serde_json::to_string(&entity).unwrap();
+    assert_eq!(json, r#"{"name":"player","x":1,"y":-2}"#);
+    assert_eq!(serde_json::from_str::<Entity>(&json).unwrap(), entity);
+
+    let value = serde_json::to_value(&entity).unwrap();
+    assert_eq!(value["x"], 1);
+    assert_eq!(value["y"], -2);
+    assert!(value.get("position").is_none());
+}
@@ -3,8 +3,10 @@
 use minibox::MiniBox;
 use static_alloc::Bump;
 
+// the test harness (threads, output capture) also allocates from the global
+// allocator, 64KiB isn't enough for it on current toolchains
 #[global_allocator]
-static A: Bump<[u8; 1 << 16]> = Bump::uninit();
+static A: Bump<[u8; 1 << 20]> = Bump::uninit();
 
 #[test]
 fn smoke() {
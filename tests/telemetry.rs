@@ -0,0 +1,59 @@
+//! This is in its own test binary because the allocation observer is global
+
+#![cfg(feature = "telemetry")]
+
+use minibox::telemetry::{clear_alloc_observer, set_alloc_observer};
+use minibox::{MiniBox, MiniOption, SizeClass};
+
+use std::alloc::Layout;
+use std::sync::Mutex;
+
+static SEEN: Mutex<Vec<(SizeClass, Layout)>> = Mutex::new(Vec::new());
+
+fn seen() -> Vec<(SizeClass, Layout)> {
+    std::mem::take(&mut *SEEN.lock().unwrap())
+}
+
+#[test]
+fn alloc_observer() {
+    set_alloc_observer(|size_class, layout| SEEN.lock().unwrap().push((size_class, layout)));
+
+    // inline and zero-sized values don't allocate
+    let _ = MiniBox::new(1_u8);
+    let _ = MiniBox::new(());
+    let _ = MiniBox::<usize>::new_uninit();
+    let _ = MiniBox::<u32>::zeroed();
+    assert_eq!(seen(), []);
+
+    let _ = MiniBox::new([1_u64; 4]);
+    let _ = MiniBox::<[u8; 100]>::new_zeroed();
+    let _ = MiniBox::<[u16; 8]>::try_new_uninit().unwrap();
+    assert_eq!(
+        seen(),
+        [
+            (SizeClass::Boxed, Layout::new::<[u64; 4]>()),
+            (SizeClass::Boxed, Layout::new::<[u8; 100]>()),
+            (SizeClass::Boxed, Layout::new::<[u16; 8]>()),
+        ]
+    );
+
+    // reallocations are reported with the new layout
+    let bx = MiniBox::map_realloc(MiniBox::new([1_u64; 4]), |x| [x[0]; 8]);
+    assert_eq!(*bx, [1; 8]);
+    assert_eq!(
+        seen(),
+        [
+            (SizeClass::Boxed, Layout::new::<[u64; 4]>()),
+            (SizeClass::Boxed, Layout::new::<[u64; 8]>()),
+        ]
+    );
+
+    // `MiniOption` allocates through `MiniBox`
+    let _ = MiniOption::some([1_u64; 4]);
+    let _ = MiniOption::some(1_u8);
+    assert_eq!(seen(), [(SizeClass::Boxed, Layout::new::<[u64; 4]>())]);
+
+    clear_alloc_observer();
+    let _ = MiniBox::new([1_u64; 4]);
+    assert_eq!(seen(), []);
+}
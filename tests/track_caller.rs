@@ -0,0 +1,33 @@
+//! This is in its own test binary because it replaces the global panic hook
+
+use minibox::MiniBox;
+
+use std::panic;
+use std::sync::Mutex;
+
+static LOCATION: Mutex<Option<(String, u32)>> = Mutex::new(None);
+
+#[test]
+#[cfg(debug_assertions)]
+fn assert_inline_debug() {
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().unwrap();
+        *LOCATION.lock().unwrap() = Some((location.file().to_string(), location.line()));
+    }));
+
+    MiniBox::<usize>::assert_inline_debug();
+    MiniBox::<[u8; 0]>::assert_inline_debug();
+
+    let line = line!() + 1;
+    let result = panic::catch_unwind(|| MiniBox::<[usize; 2]>::assert_inline_debug());
+
+    let _ = panic::take_hook();
+
+    assert!(result.is_err());
+    let payload = result.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert!(message.contains("is not stored inline"), "{}", message);
+
+    let location = LOCATION.lock().unwrap().take().unwrap();
+    assert_eq!(location, (file!().to_string(), line));
+}